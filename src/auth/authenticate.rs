@@ -0,0 +1,121 @@
+use crate::auth::{ApiToken, Caller};
+use crate::types::{CustomError, MyError};
+
+// Caller of an `authorization: Bearer <token>` header, anonymous with every project and tenant when no token is configured.
+// The `tenant` header selects the configuration of the request.
+pub fn authenticate(tokens: &[ApiToken], authorization: Option<&str>, tenant: Option<&str>) -> Result<Caller, MyError> {
+    let tenant = tenant.map(str::trim).unwrap_or_default().to_string();
+    if tokens.is_empty() {
+        return Ok(Caller { name: "anonymous".to_string(), projects: vec![], tenants: vec![], tenant });
+    }
+
+    let presented = authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+        .ok_or_else(|| CustomError::auth("Missing bearer token"))?;
+
+    tokens
+        .iter()
+        .find(|token| constant_time_eq(token.token.expose().as_bytes(), presented.as_bytes()))
+        .map(|token| Caller {
+            name: token.name.clone(),
+            projects: token.projects.clone(),
+            tenants: token.tenants.clone(),
+            // A caller of a single tenant does not need to name it
+            tenant: match token.tenants.as_slice() {
+                [only] if tenant.is_empty() => only.clone(),
+                _ => tenant,
+            },
+        })
+        .ok_or_else(|| CustomError::auth("Invalid bearer token"))
+}
+
+// Comparison time does not depend on where the bytes differ
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Secret;
+    use crate::models::AppConfig;
+
+    fn token(name: &str, token: &str, tenants: &[&str]) -> ApiToken {
+        ApiToken {
+            name: name.to_string(),
+            token: Secret::new(token.to_string()),
+            projects: vec![],
+            tenants: tenants.iter().map(|tenant| tenant.to_string()).collect(),
+        }
+    }
+
+    fn tokens() -> Vec<ApiToken> {
+        vec![token("ci", "ci-token", &[]), token("mayorana", "mayorana-token", &["mayorana"])]
+    }
+
+    fn error_kind(result: Result<Caller, MyError>) -> (&'static str, String) {
+        let e = result.unwrap_err();
+        (CustomError::kind(e.as_ref()), e.to_string())
+    }
+
+    #[test]
+    fn anonymous_without_tokens() {
+        let caller = authenticate(&[], None, Some(" mayorana ")).unwrap();
+        assert_eq!((caller.name.as_str(), caller.tenant.as_str()), ("anonymous", "mayorana"));
+        assert!(caller.projects.is_empty() && caller.tenants.is_empty());
+    }
+
+    #[test]
+    fn missing_bearer_token() {
+        let (kind, message) = error_kind(authenticate(&tokens(), None, None));
+        assert_eq!(kind, "auth");
+        assert!(message.contains("Missing bearer token"), "{}", message);
+        assert!(authenticate(&tokens(), Some("Basic Y2k6Y2k="), None).is_err());
+    }
+
+    #[test]
+    fn invalid_bearer_token() {
+        let (kind, message) = error_kind(authenticate(&tokens(), Some("Bearer ci-tokens"), None));
+        assert_eq!(kind, "auth");
+        assert!(message.contains("Invalid bearer token"), "{}", message);
+    }
+
+    #[test]
+    fn caller_of_a_valid_token() {
+        let caller = authenticate(&tokens(), Some("Bearer ci-token "), Some("mayorana")).unwrap();
+        assert_eq!((caller.name.as_str(), caller.tenant.as_str()), ("ci", "mayorana"));
+    }
+
+    #[test]
+    fn single_tenant_token_defaults_to_its_tenant() {
+        let caller = authenticate(&tokens(), Some("Bearer mayorana-token"), None).unwrap();
+        assert_eq!(caller.tenant, "mayorana");
+        // An explicit tenant is kept, and refused by `Caller::config`
+        let caller = authenticate(&tokens(), Some("Bearer mayorana-token"), Some("other")).unwrap();
+        assert_eq!(caller.tenant, "other");
+    }
+
+    #[test]
+    fn caller_config_rejects_the_other_tenants() {
+        let mut config = AppConfig::default();
+        config.tenants.insert("mayorana".to_string(), AppConfig { output_folder: "mayorana".to_string(), ..AppConfig::default() });
+        config.tenants.insert("other".to_string(), AppConfig::default());
+
+        let caller = authenticate(&tokens(), Some("Bearer mayorana-token"), None).unwrap();
+        assert_eq!(caller.config(&config).unwrap().output_folder, "mayorana");
+
+        let caller = authenticate(&tokens(), Some("Bearer mayorana-token"), Some("other")).unwrap();
+        let e = caller.config(&config).unwrap_err();
+        assert_eq!(CustomError::kind(e.as_ref()), "auth");
+        assert!(e.to_string().contains("Not allowed to use tenant 'other'"), "{}", e);
+
+        // The top level configuration needs every tenant
+        let caller = Caller { tenant: String::new(), ..caller };
+        assert!(caller.config(&config).unwrap_err().to_string().contains("A tenant is required"));
+
+        // A caller of every tenant still needs a known one
+        let caller = authenticate(&tokens(), Some("Bearer ci-token"), Some("unknown")).unwrap();
+        assert_eq!(CustomError::kind(caller.config(&config).unwrap_err().as_ref()), "not_found");
+    }
+}
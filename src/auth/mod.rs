@@ -0,0 +1,117 @@
+pub mod authenticate;
+pub mod resolve_api_tokens;
+
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::credentials::Secret;
+use crate::jobs::{Job, ScanResults};
+use crate::models::AppConfig;
+use crate::services::load_results::load_results;
+use crate::types::{CustomError, MyError};
+
+// Token of a caller with the projects it may scan and the tenants it may use, resolved once at boot
+#[derive(Debug, Clone)]
+pub struct ApiToken {
+    pub name: String,
+    pub token: Secret,
+    pub projects: Vec<String>,
+    pub tenants: Vec<String>,
+}
+
+// Who sent a request, attached to it by the servers
+#[derive(Debug, Clone, Default)]
+pub struct Caller {
+    pub name: String,
+    pub projects: Vec<String>, // Every project when empty
+    pub tenants: Vec<String>,  // Every tenant when empty
+    pub tenant: String,        // Tenant of the request, the top level configuration when empty
+}
+
+impl Caller {
+    // Configuration of the tenant of the request, when the caller may use it
+    pub fn config<'a>(&self, config: &'a AppConfig) -> Result<&'a AppConfig, MyError> {
+        if !self.tenants.is_empty() && !self.tenants.contains(&self.tenant) {
+            return Err(match self.tenant.as_str() {
+                "" => CustomError::auth("A tenant is required, set the `tenant` header"),
+                tenant => CustomError::auth(format!("Not allowed to use tenant '{}'", tenant)),
+            });
+        }
+        config_for_tenant(config, &self.tenant)
+    }
+
+    // Projects of the results it may read, the ones it may scan
+    pub fn may_read(&self, project: &str) -> bool {
+        self.projects.is_empty() || self.projects.iter().any(|allowed| allowed.eq_ignore_ascii_case(project))
+    }
+
+    // Jobs of the tenant of the request it queued or joined, and the ones of the schedules and messages when it may read
+    // every project. Anonymous callers share a name, they see every job without tokens
+    pub fn may_watch(&self, job: &Job) -> bool {
+        let owners = job.owners();
+        job.tenant == self.tenant
+            && (self.tenants.is_empty() || self.tenants.contains(&self.tenant))
+            && (owners.contains(&self.name) || (owners.is_empty() && self.projects.is_empty()))
+    }
+
+    // Last results of the tenant of the request, without the projects the caller may not read
+    pub fn load_results(&self, config: &AppConfig, project: Option<&str>) -> Result<ScanResults, MyError> {
+        if let Some(project) = project.filter(|project| !self.may_read(project)) {
+            return Err(CustomError::auth(format!("Not allowed to read project '{}'", project)));
+        }
+        let results = load_results(self.config(config)?, project)?;
+        Ok(results.into_iter().filter(|(project, _)| self.may_read(project)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use crate::jobs::JobRegistry;
+
+    fn caller(name: &str, projects: &[&str]) -> Caller {
+        Caller { name: name.to_string(), projects: projects.iter().map(|project| project.to_string()).collect(), ..Caller::default() }
+    }
+
+    #[test]
+    fn may_read_its_projects_only() {
+        assert!(caller("ci", &[]).may_read("PTEP"));
+        assert!(caller("ptep", &["PTEP"]).may_read("ptep"));
+        assert!(!caller("ptep", &["PTEP"]).may_read("SES"));
+    }
+
+    #[test]
+    fn load_results_without_the_other_projects() {
+        let folder = std::env::temp_dir().join(format!("xdebt-caller-results-{}", std::process::id()));
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("all_projects.json"), r#"{"PTEP": [], "SES": []}"#).unwrap();
+        let config = AppConfig { output_folder: folder.display().to_string(), ..AppConfig::default() };
+
+        let results = caller("ptep", &["ptep"]).load_results(&config, None).unwrap();
+        assert_eq!(results.keys().collect::<Vec<_>>(), ["PTEP"]);
+        assert_eq!(caller("ci", &[]).load_results(&config, None).unwrap().len(), 2);
+
+        let e = caller("ptep", &["ptep"]).load_results(&config, Some("SES")).unwrap_err();
+        assert_eq!(CustomError::kind(e.as_ref()), "auth");
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn may_watch_its_jobs_of_its_tenant() {
+        let jobs = JobRegistry::default();
+        let owned = jobs.create("1", "my-repo", "", &["alice".to_string()]);
+        let scheduled = jobs.create("2", "all", "", &[]);
+        let tenant = jobs.create("3", "my-repo (mayorana)", "mayorana", &["alice".to_string()]);
+
+        let alice = caller("alice", &[]);
+        assert!(alice.may_watch(&owned) && alice.may_watch(&scheduled));
+        assert!(!alice.may_watch(&tenant));
+        assert!(Caller { tenant: "mayorana".to_string(), ..alice.clone() }.may_watch(&tenant));
+        assert!(!Caller { tenant: "mayorana".to_string(), tenants: vec!["other".to_string()], ..alice }.may_watch(&tenant));
+
+        // The scheduled jobs need every project
+        let bob = caller("bob", &["PTEP"]);
+        assert!(!bob.may_watch(&owned) && !bob.may_watch(&scheduled));
+        assert!(!caller("bob", &[]).may_watch(&owned));
+        assert!(caller("bob", &[]).may_watch(&scheduled));
+    }
+}
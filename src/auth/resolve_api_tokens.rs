@@ -0,0 +1,38 @@
+use std::env;
+use std::fs;
+
+use crate::auth::ApiToken;
+use crate::credentials::Secret;
+use crate::models::AuthConfig;
+use crate::types::{CustomError, MyError};
+
+// A missing or empty token is an error, it would otherwise lock the caller out silently
+pub fn resolve_api_tokens(auth: &AuthConfig) -> Result<Vec<ApiToken>, MyError> {
+    dotenv::dotenv().ok();
+
+    auth.tokens
+        .iter()
+        .map(|config| {
+            let token = match (&config.token_env, &config.token_file) {
+                (Some(var), _) => env::var(var)
+                    .map_err(|_| CustomError::auth(format!("Missing token of caller '{}', set {}", config.name, var)))?,
+                (None, Some(path)) => fs::read_to_string(path)
+                    .map_err(|e| CustomError::auth(format!("Cannot read the token of caller '{}' from {}: {}", config.name, path, e)))?
+                    .trim()
+                    .to_string(),
+                (None, None) => {
+                    return Err(CustomError::config(format!("Caller '{}' needs a `token_env` or a `token_file`", config.name)))
+                }
+            };
+            if token.is_empty() {
+                return Err(CustomError::auth(format!("Empty token for caller '{}'", config.name)));
+            }
+            Ok(ApiToken {
+                name: config.name.clone(),
+                token: Secret::new(token),
+                projects: config.projects.clone(),
+                tenants: config.tenants.clone(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,32 @@
+use std::time::Duration;
+
+use reqwest::Client;
+
+use crate::boot::validate_config::{find_key_position, ConfigDiagnostic, Severity};
+use crate::models::ConfigFile;
+
+/// Warn about base URLs that do not answer. Any HTTP status, even 401 or 404, means reachable.
+pub async fn check_base_urls(file: &str, config: &ConfigFile) -> Vec<ConfigDiagnostic> {
+    let content = std::fs::read_to_string(file).unwrap_or_default();
+    let client = match Client::builder().timeout(Duration::from_secs(10)).build() {
+        Ok(client) => client,
+        Err(_) => return vec![],
+    };
+
+    let base_urls = std::iter::once(("default", &config.base_url))
+        .filter(|(_, base_url)| !base_url.is_empty())
+        .chain(config.sources.iter().map(|source| (source.name.as_str(), &source.base_url)));
+
+    let mut diagnostics = vec![];
+    for (source, base_url) in base_urls {
+        if let Err(e) = client.get(base_url.as_str()).send().await {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Warning,
+                file,
+                find_key_position(&content, "base_url", Some(base_url), 1),
+                format!("base_url '{}' of source '{}' is unreachable: {}", base_url, source, e),
+            ));
+        }
+    }
+    diagnostics
+}
@@ -0,0 +1,59 @@
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Plugin names accepted in a selection, as in the `enable_*_analysis` keys
+pub const PLUGINS: [&str; 7] = ["maven", "npm", "docker", "dotnet", "php", "jenkins", "ci"];
+
+/// Copy of the configuration for one request: the branch to analyse (default one when empty),
+/// only the selected plugins when some are given (the configured ones otherwise),
+/// and only the projects the caller may scan when it is restricted.
+pub fn config_for_request(
+    config: &AppConfig,
+    branch: &str,
+    plugins: &[String],
+    projects: &[String],
+) -> Result<AppConfig, MyError> {
+    let mut request_config = config.clone();
+
+    if !branch.is_empty() {
+        request_config.branch = Some(branch.to_string());
+    }
+
+    if let Some(unknown) = plugins.iter().find(|plugin| !PLUGINS.contains(&plugin.as_str())) {
+        return Err(CustomError::invalid_input(format!(
+            "Unknown plugin '{}' (expected one of: {})",
+            unknown,
+            PLUGINS.join(", ")
+        )));
+    }
+    if !plugins.is_empty() {
+        let selected = |name: &str| plugins.iter().any(|plugin| plugin == name);
+        request_config.enable_maven_analysis = selected("maven");
+        request_config.enable_npm_analysis = selected("npm");
+        request_config.enable_docker_analysis = selected("docker");
+        request_config.enable_dotnet_analysis = selected("dotnet");
+        request_config.enable_php_analysis = selected("php");
+        request_config.enable_jenkins_analysis = selected("jenkins");
+        request_config.enable_ci_analysis = selected("ci");
+        request_config.plugins_selected = true;
+    }
+
+    // Repositories are only looked up in the allowed projects of each source, a source left without any is skipped
+    if !projects.is_empty() {
+        let allowed = |project: &String| projects.iter().any(|p| p.eq_ignore_ascii_case(project));
+        request_config.sources = request_config
+            .sources
+            .into_iter()
+            .filter_map(|mut source| {
+                source.projects = if source.projects.is_empty() {
+                    projects.to_vec()
+                } else {
+                    source.projects.into_iter().filter(allowed).collect()
+                };
+                (!source.projects.is_empty()).then_some(source)
+            })
+            .collect();
+    }
+
+    Ok(request_config)
+}
@@ -0,0 +1,46 @@
+use crate::credentials::Credentials;
+use crate::models::{AppConfig, SourceConfig};
+use crate::url::{bitbucket::BitbucketConfig, bitbucket_cloud::BitbucketCloudConfig, github::GithubConfig};
+use crate::url::UrlConfig;
+use crate::types::{CustomError, MyError};
+
+// Match platform and construct the corresponding URL config
+pub fn build_url_config(source: &SourceConfig, credentials: Credentials) -> Result<Box<dyn UrlConfig>, MyError> {
+    let base_url = source.base_url.clone();
+
+    let url_config: Box<dyn UrlConfig> = match source.platform.as_str() {
+        "bitbucket" => Box::new(BitbucketConfig { base_url, credentials }),
+        "bitbucket-cloud" => Box::new(BitbucketCloudConfig { base_url, credentials }),
+        "github" => Box::new(GithubConfig {
+            base_url,
+            user: source.user.clone().unwrap_or_default(),
+            credentials,
+        }),
+        other => {
+            return Err(CustomError::config(format!(
+                "Unsupported platform '{}' for source '{}'",
+                other, source.name
+            )))
+        }
+    };
+    Ok(url_config)
+}
+
+/// Copy of the configuration pointing to one source: platform, URLs, credentials and scopes.
+/// The client, database, HTTP stats and circuit breakers stay shared between sources.
+pub fn config_for_source(config: &AppConfig, source: &SourceConfig) -> Result<AppConfig, MyError> {
+    let credentials = config
+        .credentials
+        .get(&source.name)
+        .cloned()
+        .ok_or_else(|| CustomError::auth(format!("No credentials resolved for source '{}'", source.name)))?;
+
+    Ok(AppConfig {
+        platform: source.platform.clone(),
+        url_config: build_url_config(source, credentials)?.into(),
+        github: source.github.clone(),
+        bitbucket_cloud: source.bitbucket_cloud.clone(),
+        source: Some(source.clone()),
+        ..config.clone()
+    })
+}
@@ -0,0 +1,13 @@
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Configuration of a tenant, the top level one for an empty tenant
+pub fn config_for_tenant<'a>(config: &'a AppConfig, tenant: &str) -> Result<&'a AppConfig, MyError> {
+    if tenant.is_empty() {
+        return Ok(config);
+    }
+    config
+        .tenants
+        .get(tenant)
+        .ok_or_else(|| CustomError::not_found(format!("No tenant '{}'", tenant)))
+}
@@ -1,36 +1,100 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+
 use crate::boot::read_yaml::read_yaml;
-use crate::models::{AppConfig, ConfigFile};
-use crate::url::{bitbucket::BitbucketConfig, github::GithubConfig};
-use crate::utils::create_client_with_auth::create_client_with_auth;
-use crate::url::UrlConfig;
+use crate::boot::config_for_source::config_for_source;
+use crate::models::{AppConfig, ConfigFile, ConfigOverrides, SourceConfig};
+use crate::auth::resolve_api_tokens::resolve_api_tokens;
+use crate::credentials::resolve_credentials::resolve_credentials;
+use crate::utils::create_client::create_client;
 use crate::boot::init_tracing::init_tracing;
-use crate::types::MyError;
+use crate::types::{CustomError, MyError};
+
+pub fn load_config(config_file_path: &str, overrides: &ConfigOverrides) -> Result<AppConfig, MyError> {
+    let mut config: ConfigFile = read_yaml(config_file_path)?;
+    overrides.apply(&mut config);
+    init_tracing(&config.trace_level)?;
+    build_app_config(config)
+}
 
-pub fn load_config(config_file_path: &str) -> Result<AppConfig, MyError> {
-    let config: ConfigFile = read_yaml(config_file_path)?;
-    let _ = init_tracing(&config.trace_level.to_string())?;
+// Tracing can only be initialized once, a reload keeps the initial trace level
+pub fn reload_config(config_file_path: &str, overrides: &ConfigOverrides) -> Result<AppConfig, MyError> {
+    let mut config: ConfigFile = read_yaml(config_file_path)?;
+    overrides.apply(&mut config);
+    build_app_config(config)
+}
 
-    let (client, _, _) = create_client_with_auth(config.platform.clone())?;
+fn build_app_config(config: ConfigFile) -> Result<AppConfig, MyError> {
+    // Built from the file before the top level one takes its keys
+    let mut tenants = HashMap::new();
+    for (name, tenant) in &config.tenants {
+        let mut tenant_file = config.clone();
+        tenant.apply(name, &mut tenant_file);
+        let tenant_config = build_app_config(tenant_file)
+            .map_err(|e| CustomError::config(format!("Tenant '{}': {}", name, e)))?;
+        tenants.insert(name.clone(), AppConfig { tenant: Some(name.clone()), ..tenant_config });
+    }
 
-    // Match platform and construct the corresponding URL config
-    let url_config: Box<dyn UrlConfig> = match config.platform.as_str() {
-        "bitbucket" => Box::new(BitbucketConfig {
-            base_url: config.base_url.clone(),
-        }),
-        "github" => Box::new(GithubConfig {
+    // Without `sources`, the top level platform is the only source
+    let sources = if config.sources.is_empty() {
+        if config.platform.is_empty() {
+            return Err(CustomError::config("Either `platform` and `base_url` or `sources` must be set"));
+        }
+        vec![SourceConfig {
+            name: config.platform.clone(),
+            platform: config.platform.clone(),
             base_url: config.base_url.clone(),
-            user: config.user.clone().unwrap_or_default(),
-        }),
-        _ => return Err("Unsupported platform".into()),
+            user: config.user.clone(),
+            env_prefix: None,
+            credentials: config.credentials.clone(),
+            github: config.github.clone(),
+            bitbucket_cloud: config.bitbucket_cloud.clone(),
+            projects: vec![],
+            exclude_repos: vec![],
+            exclude_patterns: vec![],
+        }]
+    } else {
+        config.sources.clone()
     };
 
-    Ok(AppConfig {
+    // Compiled once, every repository of a scan is checked against them
+    let mut sources = sources;
+    for source in &mut sources {
+        source.exclude_patterns = source
+            .exclude_repos
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    CustomError::config(format!("Invalid exclude_repos pattern '{}' of source '{}': {}", pattern, source.name, e))
+                })
+            })
+            .collect::<Result<_, _>>()?;
+    }
+
+    // Source names tag the results and key the scan states, they must be unique
+    let mut names = HashSet::new();
+    if let Some(duplicate) = sources.iter().find(|source| !names.insert(&source.name)) {
+        return Err(CustomError::config(format!("Duplicate source name '{}'", duplicate.name)));
+    }
+
+    // Credentials are resolved once, requests only attach them
+    let mut credentials = HashMap::new();
+    for source in &sources {
+        credentials.insert(source.name.clone(), resolve_credentials(source)?);
+    }
+    let api_tokens = resolve_api_tokens(&config.auth)?;
+
+    // The first source is the default one (menu, gRPC)
+    let first_source = &sources[0];
+    let client = create_client(&config.http)?;
+
+    let app_config = AppConfig {
         client,
         db: None, // Initialized later on
         platform: config.platform,
         output_folder: config.output_folder,
         roadmap_folder: config.roadmap_folder,
-        url_config: url_config.into(),
         force_git_pull: config.force_git_pull,
         force_maven_effective: config.force_maven_effective,
         force_sled_db_sourcing: config.force_sled_db_sourcing,
@@ -42,5 +106,30 @@ pub fn load_config(config_file_path: &str) -> Result<AppConfig, MyError> {
         enable_dotnet_analysis: config.enable_dotnet_analysis,
         enable_php_analysis: config.enable_php_analysis,
         enable_jenkins_analysis: config.enable_jenkins_analysis,
-    })
+        enable_ci_analysis: config.enable_ci_analysis,
+        http: config.http,
+        incremental: config.incremental,
+        github: config.github,
+        bitbucket_cloud: config.bitbucket_cloud,
+        sources: sources.clone(),
+        credentials,
+        concurrency: config.concurrency.max(1),
+        policy: config.policy,
+        jobs: config.jobs,
+        schedules: config.schedules,
+        rest: config.rest,
+        grpc: config.grpc,
+        iggy: config.iggy,
+        api_tokens,
+        ..Default::default()
+    };
+
+    // HTTP counters and circuit breakers cover the hosts of every tenant
+    let mut app_config = config_for_source(&app_config, first_source)?;
+    for tenant in tenants.values_mut() {
+        tenant.http_stats = app_config.http_stats.clone();
+        tenant.circuit_breakers = app_config.circuit_breakers.clone();
+    }
+    app_config.tenants = tenants;
+    Ok(app_config)
 }
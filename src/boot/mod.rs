@@ -1,4 +1,11 @@
-// pub mod watch_config_for_reload;
+pub mod watch_config_for_reload;
 pub mod load_config;
 pub mod read_yaml;
 pub mod init_tracing;
+pub mod config_for_source;
+pub mod validate_config;
+pub mod check_base_urls;
+pub mod shared_config;
+pub mod config_for_request;
+pub mod config_for_tenant;
+pub mod open_tenant_databases;
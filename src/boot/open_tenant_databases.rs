@@ -0,0 +1,24 @@
+use std::path::Path;
+
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Folder of the tenant databases, one sled database per tenant next to `roadmap_db`
+pub const TENANT_DB_FOLDER: &str = "tenant_db";
+
+// Roadmap, scan states and HTTP cache of a tenant are kept apart from the other tenants.
+// Databases already open (reload) are not opened again, the names of the opened ones are returned.
+pub fn open_tenant_databases(config: &mut AppConfig) -> Result<Vec<String>, MyError> {
+    let mut opened = vec![];
+    for (name, tenant) in config.tenants.iter_mut().filter(|(_, tenant)| tenant.db.is_none()) {
+        let path = Path::new(TENANT_DB_FOLDER).join(name);
+        let db = sled::open(&path).map_err(|e| CustomError::Database {
+            context: format!("Failed to open {}", path.display()),
+            source: e,
+        })?;
+        tracing::info!("Database of tenant '{}': {} ({} items)", name, path.display(), db.len());
+        tenant.db = Some(db);
+        opened.push(name.clone());
+    }
+    Ok(opened)
+}
@@ -7,6 +7,7 @@ use std::fmt;
 use crate::models::ConfigFile;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum ConfigError {
     FileOpenError(String),
     FileReadError(String),
@@ -27,7 +28,7 @@ impl Error for ConfigError {}
 
 use crate::types::MyError;
 pub fn read_yaml(file_path: &str) -> Result<ConfigFile, MyError> {
-    let mut file = File::open(&file_path)
+    let mut file = File::open(file_path)
         .map_err(|e| ConfigError::FileOpenError(format!("{}: {}", file_path, e)))?;
 
     let mut contents = String::new();
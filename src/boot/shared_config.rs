@@ -0,0 +1,24 @@
+use std::sync::{Arc, RwLock};
+
+use crate::models::AppConfig;
+
+/// Configuration swapped atomically on reload. Each request takes a snapshot with `current`,
+/// so analyses in flight finish with the configuration they started with.
+#[derive(Debug, Clone, Default)]
+pub struct SharedConfig {
+    inner: Arc<RwLock<Arc<AppConfig>>>,
+}
+
+impl SharedConfig {
+    pub fn new(config: AppConfig) -> Self {
+        SharedConfig { inner: Arc::new(RwLock::new(Arc::new(config))) }
+    }
+
+    pub fn current(&self) -> Arc<AppConfig> {
+        Arc::clone(&self.inner.read().unwrap())
+    }
+
+    pub fn replace(&self, config: AppConfig) {
+        *self.inner.write().unwrap() = Arc::new(config);
+    }
+}
@@ -0,0 +1,585 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::net::SocketAddr;
+use std::path::Path;
+
+use croner::Cron;
+use regex::Regex;
+use serde_json::Value as JsonValue;
+use serde_yaml::Value as YamlValue;
+
+use crate::models::{ConfigFile, JobScope, Roadmaps};
+
+// JSON schema of the configuration file, also the reference for unknown keys
+pub const CONFIG_SCHEMA: &str = include_str!("../../configuration.schema.json");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug)]
+pub struct ConfigDiagnostic {
+    pub severity: Severity,
+    pub file: String,
+    pub line: Option<usize>, // 1-based
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl ConfigDiagnostic {
+    pub fn new(severity: Severity, file: &str, position: Option<(usize, usize)>, message: String) -> Self {
+        ConfigDiagnostic {
+            severity,
+            file: file.to_string(),
+            line: position.map(|(line, _)| line),
+            column: position.map(|(_, column)| column),
+            message,
+        }
+    }
+}
+
+// file:line:column: error: message, the format understood by editors and CI annotations
+impl fmt::Display for ConfigDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "{}:{}:{}: {}: {}", self.file, line, column, severity, self.message),
+            _ => write!(f, "{}: {}: {}", self.file, severity, self.message),
+        }
+    }
+}
+
+pub fn has_errors(diagnostics: &[ConfigDiagnostic]) -> bool {
+    diagnostics.iter().any(|d| d.severity == Severity::Error)
+}
+
+// Printed on stderr, tracing is not initialized before the configuration is valid
+pub fn print_diagnostics(diagnostics: &[ConfigDiagnostic]) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+}
+
+// Line and column of `key:` (with `value` when given), searched from `from_line`, comments skipped
+pub fn find_key_position(content: &str, key: &str, value: Option<&str>, from_line: usize) -> Option<(usize, usize)> {
+    content
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .find_map(|(index, line)| {
+            let trimmed = line.trim_start().trim_start_matches("- ").trim_start();
+            if trimmed.starts_with('#') {
+                return None;
+            }
+            let rest = trimmed.strip_prefix(key)?.trim_start().strip_prefix(':')?;
+            if value.map(|value| rest.contains(value)).unwrap_or(true) {
+                Some((index + 1, line.len() - trimmed.len() + 1))
+            } else {
+                None
+            }
+        })
+}
+
+// Line and column of a list item, `value` searched from `from_line` in the lines that are not comments
+fn find_value_position(content: &str, value: &str, from_line: usize) -> Option<(usize, usize)> {
+    content
+        .lines()
+        .enumerate()
+        .skip(from_line.saturating_sub(1))
+        .filter(|(_, line)| !line.trim_start().starts_with('#'))
+        .find_map(|(index, line)| line.find(value).map(|column| (index + 1, column + 1)))
+}
+
+// Only local references to $defs are used in the schema
+fn resolve_ref<'a>(root: &'a JsonValue, schema: &'a JsonValue) -> &'a JsonValue {
+    match schema["$ref"].as_str().and_then(|r| r.strip_prefix("#/$defs/")) {
+        Some(name) => &root["$defs"][name],
+        None => schema,
+    }
+}
+
+// What the unknown keys check needs along the walk
+struct SchemaWalk<'a> {
+    root: &'a JsonValue,
+    content: &'a str,
+    file: &'a str,
+}
+
+// Keys absent from the schema, recursively
+fn check_unknown_keys(
+    walk: &SchemaWalk,
+    schema: &JsonValue,
+    value: &YamlValue,
+    path: &str,
+    from_line: usize,
+    diagnostics: &mut Vec<ConfigDiagnostic>,
+) {
+    let (content, file) = (walk.content, walk.file);
+    let schema = resolve_ref(walk.root, schema);
+    match value {
+        YamlValue::Mapping(mapping) => {
+            for (key, child) in mapping {
+                let Some(key) = key.as_str() else { continue };
+                let position = find_key_position(content, key, None, from_line);
+                let child_line = position.map(|(line, _)| line).unwrap_or(from_line);
+                let child_path = if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+
+                match schema["properties"].get(key) {
+                    Some(child_schema) => check_unknown_keys(walk, child_schema, child, &child_path, child_line, diagnostics),
+                    None if schema["additionalProperties"] == JsonValue::Bool(false) => {
+                        let known: Vec<&str> = schema["properties"]
+                            .as_object()
+                            .map(|properties| properties.keys().map(String::as_str).collect())
+                            .unwrap_or_default();
+                        let hint = known
+                            .iter()
+                            .find(|candidate| is_close(candidate, key))
+                            .map(|candidate| format!(", did you mean '{}'?", candidate))
+                            .unwrap_or_else(|| format!(" (expected one of: {})", known.join(", ")));
+                        diagnostics.push(ConfigDiagnostic::new(
+                            Severity::Error,
+                            file,
+                            position,
+                            format!("unknown key '{}'{}", child_path, hint),
+                        ));
+                    }
+                    None => {
+                        if let Some(child_schema) = schema.get("additionalProperties").filter(|s| s.is_object()) {
+                            check_unknown_keys(walk, child_schema, child, &child_path, child_line, diagnostics);
+                        }
+                    }
+                }
+            }
+        }
+        YamlValue::Sequence(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                let mut line = from_line;
+                for (index, item) in items.iter().enumerate() {
+                    // Items start with "- ", move past the previous one
+                    line = content
+                        .lines()
+                        .enumerate()
+                        .skip(line)
+                        .find(|(_, l)| l.trim_start().starts_with("- "))
+                        .map(|(i, _)| i + 1)
+                        .unwrap_or(line);
+                    check_unknown_keys(walk, item_schema, item, &format!("{}[{}]", path, index), line, diagnostics);
+                }
+            }
+        }
+        // Closed lists of values: platform, trace_level, credentials provider
+        YamlValue::String(text) => {
+            if let Some(allowed) = schema["enum"].as_array() {
+                if !allowed.iter().any(|v| v.as_str() == Some(text)) {
+                    let key = path.rsplit('.').next().unwrap_or(path);
+                    let expected: Vec<&str> = allowed.iter().filter_map(JsonValue::as_str).collect();
+                    diagnostics.push(ConfigDiagnostic::new(
+                        Severity::Error,
+                        file,
+                        find_key_position(content, key, Some(text), from_line),
+                        format!("unsupported value '{}' for '{}' (expected one of: {})", text, path, expected.join(", ")),
+                    ));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+// Same length and at most two different characters (typo, swapped letters),
+// or one character inserted or missing
+fn is_close(candidate: &str, key: &str) -> bool {
+    let (a, b): (Vec<char>, Vec<char>) = (candidate.chars().collect(), key.chars().collect());
+    if a.len() == b.len() {
+        return a.iter().zip(&b).filter(|(x, y)| x != y).count() <= 2;
+    }
+    let (long, short) = if a.len() > b.len() { (&a, &b) } else { (&b, &a) };
+    if long.len() - short.len() != 1 {
+        return false;
+    }
+    (0..long.len()).any(|skip| {
+        long.iter().enumerate().filter(|(i, _)| *i != skip).map(|(_, c)| c).eq(short.iter())
+    })
+}
+
+// Products declared in the roadmap files, errors for unreadable files
+fn roadmap_products(roadmap_folder: &str, diagnostics: &mut Vec<ConfigDiagnostic>) -> HashSet<String> {
+    let mut products = HashSet::new();
+    let Ok(entries) = fs::read_dir(roadmap_folder) else { return products };
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().and_then(|s| s.to_str()) != Some("yml") {
+            continue;
+        }
+        let file = path.display().to_string();
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| (None, e.to_string()))
+            .and_then(|content| {
+                serde_yaml::from_str::<Roadmaps>(&content)
+                    .map_err(|e| (e.location().map(|l| (l.line(), l.column())), e.to_string()))
+            });
+        match parsed {
+            Ok(roadmaps) => products.extend(roadmaps.roadmap_list.into_iter().map(|r| r.product.to_lowercase())),
+            Err((position, message)) => diagnostics.push(ConfigDiagnostic::new(Severity::Error, &file, position, message)),
+        }
+    }
+    products
+}
+
+/// Static checks of a configuration file: YAML syntax, unknown keys and values, types, platforms,
+/// roadmap folder and equivalences. The parsed file is returned when it can be used.
+pub fn validate_config(file: &str) -> (Option<ConfigFile>, Vec<ConfigDiagnostic>) {
+    let mut diagnostics = vec![];
+
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(e) => {
+            diagnostics.push(ConfigDiagnostic::new(Severity::Error, file, None, format!("cannot read the file: {}", e)));
+            return (None, diagnostics);
+        }
+    };
+
+    // Syntax
+    let yaml: YamlValue = match serde_yaml::from_str(&content) {
+        Ok(yaml) => yaml,
+        Err(e) => {
+            let position = e.location().map(|l| (l.line(), l.column()));
+            diagnostics.push(ConfigDiagnostic::new(Severity::Error, file, position, format!("invalid YAML: {}", e)));
+            return (None, diagnostics);
+        }
+    };
+
+    // Unknown keys, serde ignores them silently
+    let schema: JsonValue = serde_json::from_str(CONFIG_SCHEMA).expect("configuration.schema.json is valid JSON");
+    let walk = SchemaWalk { root: &schema, content: &content, file };
+    check_unknown_keys(&walk, &schema, &yaml, "", 1, &mut diagnostics);
+
+    // Missing keys and wrong types
+    let config: ConfigFile = match serde_yaml::from_str(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            let position = e.location().map(|l| (l.line(), l.column()));
+            diagnostics.push(ConfigDiagnostic::new(Severity::Error, file, position, e.to_string()));
+            return (None, diagnostics);
+        }
+    };
+
+    // Platforms (supported values are checked against the schema)
+    if config.sources.is_empty() && config.platform.is_empty() {
+        diagnostics.push(ConfigDiagnostic::new(
+            Severity::Error,
+            file,
+            None,
+            "either `platform` and `base_url` or `sources` must be set".to_string(),
+        ));
+    }
+
+    let mut names = HashSet::new();
+    for source in &config.sources {
+        if !names.insert(&source.name) {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                find_key_position(&content, "name", Some(&source.name), 1),
+                format!("duplicate source name '{}'", source.name),
+            ));
+        }
+    }
+
+    // Jobs, each one is searched after the previous one
+    let mut job_line = find_key_position(&content, "jobs", None, 1).map(|(line, _)| line).unwrap_or(1);
+    for job in &config.jobs {
+        let position = find_key_position(&content, "scan", None, job_line);
+        job_line = position.map(|(line, _)| line + 1).unwrap_or(job_line);
+        if job.scan != JobScope::All && job.name.as_deref().unwrap_or_default().is_empty() {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                "a `repo` or `project` job needs a `name`".to_string(),
+            ));
+        }
+    }
+
+    // Schedules, positioned like the jobs
+    let mut schedule_line = find_key_position(&content, "schedules", None, 1).map(|(line, _)| line).unwrap_or(1);
+    for schedule in &config.schedules {
+        let position = find_key_position(&content, "cron", None, schedule_line);
+        schedule_line = position.map(|(line, _)| line + 1).unwrap_or(schedule_line);
+        if let Err(e) = Cron::new(&schedule.cron).parse() {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                format!("invalid cron expression '{}': {}", schedule.cron, e),
+            ));
+        }
+        if schedule.job.scan != JobScope::All && schedule.job.name.as_deref().unwrap_or_default().is_empty() {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                "a `repo` or `project` schedule needs a `name`".to_string(),
+            ));
+        }
+    }
+
+    // Servers of `xdebt serve`
+    for (key, address) in [("grpc", &config.grpc.address), ("rest", &config.rest.address)] {
+        if address.parse::<SocketAddr>().is_err() {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                find_key_position(&content, key, None, 1),
+                format!("{}.address '{}' is not an address like 0.0.0.0:50052", key, address),
+            ));
+        }
+    }
+    if let Some(tls) = &config.grpc.tls {
+        for (key, path) in [("cert", Some(&tls.cert)), ("key", Some(&tls.key)), ("client_ca", tls.client_ca.as_ref())] {
+            if let Some(path) = path.filter(|path| !Path::new(path).is_file()) {
+                diagnostics.push(ConfigDiagnostic::new(
+                    Severity::Error,
+                    file,
+                    find_key_position(&content, key, None, 1),
+                    format!("TLS file '{}' does not exist", path),
+                ));
+            }
+        }
+    }
+    for token in &config.auth.tokens {
+        if token.token_env.is_none() && token.token_file.is_none() {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                find_key_position(&content, "name", Some(&token.name), 1),
+                format!("caller '{}' needs a `token_env` or a `token_file`", token.name),
+            ));
+        }
+    }
+
+    // Iggy consumer, it must not read its own results nor share the stream of a tenant
+    if config.iggy.enabled {
+        let position = find_key_position(&content, "iggy", None, 1);
+        let topics = [
+            ("topic", &config.iggy.topic),
+            ("result_topic", &config.iggy.result_topic),
+            ("dead_letter_topic", &config.iggy.dead_letter_topic),
+        ];
+        for (i, (key, topic)) in topics.iter().enumerate() {
+            if let Some((other, _)) = topics[..i].iter().find(|(_, other)| other == topic) {
+                diagnostics.push(ConfigDiagnostic::new(
+                    Severity::Error,
+                    file,
+                    position,
+                    format!("iggy.{} and iggy.{} are both '{}'", other, key, topic),
+                ));
+            }
+        }
+        if config.tenants.contains_key(&config.iggy.stream) {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                format!("iggy.stream '{}' is the stream of the tenant of the same name", config.iggy.stream),
+            ));
+        }
+    }
+
+    // Tenants name folders and databases, their own roadmap folder must exist
+    let tenants_line = find_key_position(&content, "tenants", None, 1).map(|(line, _)| line).unwrap_or(1);
+    let mut tenants: Vec<&String> = config.tenants.keys().collect();
+    tenants.sort();
+    for name in tenants {
+        let tenant = &config.tenants[name];
+        let position = find_key_position(&content, name, None, tenants_line);
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                format!("tenant name '{}' may only contain letters, digits, '-' and '_'", name),
+            ));
+        }
+        if let Some(roadmap_folder) = tenant.roadmap_folder.as_ref().filter(|folder| !Path::new(folder).is_dir()) {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                position,
+                format!("roadmap folder '{}' of tenant '{}' does not exist", roadmap_folder, name),
+            ));
+        }
+        // Results are read by file name, a folder inside another one would expose them to its callers
+        let output_folder = tenant.output_folder(name, &config.output_folder);
+        let other_folders = std::iter::once(("the top level configuration".to_string(), config.output_folder.clone())).chain(
+            config
+                .tenants
+                .iter()
+                .filter(|(other, _)| other.as_str() > name.as_str()) // Each pair once
+                .map(|(other, tenant)| (format!("tenant '{}'", other), tenant.output_folder(other, &config.output_folder))),
+        );
+        for (owner, other_folder) in other_folders {
+            let (folder, other_folder) = (Path::new(&output_folder), Path::new(&other_folder));
+            if folder.starts_with(other_folder) || other_folder.starts_with(folder) {
+                diagnostics.push(ConfigDiagnostic::new(
+                    Severity::Error,
+                    file,
+                    position,
+                    format!("output folder '{}' of tenant '{}' overlaps the one of {}", output_folder, name, owner),
+                ));
+            }
+        }
+        let mut names = HashSet::new();
+        for source in tenant.sources.iter().flatten() {
+            if !names.insert(&source.name) {
+                diagnostics.push(ConfigDiagnostic::new(
+                    Severity::Error,
+                    file,
+                    position,
+                    format!("duplicate source name '{}' in tenant '{}'", source.name, name),
+                ));
+            }
+        }
+    }
+    let tenant_references = config
+        .jobs
+        .iter()
+        .chain(config.schedules.iter().map(|schedule| &schedule.job))
+        .filter_map(|job| job.tenant.as_ref())
+        .chain(config.auth.tokens.iter().flat_map(|token| &token.tenants));
+    for tenant in tenant_references {
+        if !config.tenants.contains_key(tenant) {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Error,
+                file,
+                find_key_position(&content, "tenant", Some(tenant), 1)
+                    .or_else(|| find_key_position(&content, "tenants", Some(tenant), 1)),
+                format!("unknown tenant '{}'", tenant),
+            ));
+        }
+    }
+
+    // Repository filters of the sources, top level and tenants, each one searched from its source name
+    let sources = config.sources.iter().chain(config.tenants.values().flat_map(|tenant| tenant.sources.iter().flatten()));
+    for source in sources {
+        let source_line = find_key_position(&content, "name", Some(&source.name), 1).map(|(line, _)| line).unwrap_or(1);
+        for pattern in &source.exclude_repos {
+            if let Err(e) = Regex::new(pattern) {
+                diagnostics.push(ConfigDiagnostic::new(
+                    Severity::Error,
+                    file,
+                    find_value_position(&content, pattern, source_line),
+                    // The regex message spans several lines, its last one is the reason
+                    format!(
+                        "invalid exclude_repos pattern '{}' of source '{}': {}",
+                        pattern,
+                        source.name,
+                        e.to_string().lines().last().unwrap_or_default().trim_start_matches("error: ")
+                    ),
+                ));
+            }
+        }
+    }
+
+    // Roadmap folder and equivalences
+    if !Path::new(&config.roadmap_folder).is_dir() {
+        diagnostics.push(ConfigDiagnostic::new(
+            Severity::Error,
+            file,
+            find_key_position(&content, "roadmap_folder", None, 1),
+            format!("roadmap folder '{}' does not exist", config.roadmap_folder),
+        ));
+    } else {
+        let products = roadmap_products(&config.roadmap_folder, &mut diagnostics);
+        let equivalences_line = find_key_position(&content, "equivalences", None, 1).map(|(line, _)| line).unwrap_or(1);
+        let mut missing: Vec<&String> = config
+            .equivalences
+            .keys()
+            .filter(|product| !products.contains(&product.to_lowercase()))
+            .collect();
+        missing.sort();
+        for product in missing {
+            diagnostics.push(ConfigDiagnostic::new(
+                Severity::Warning,
+                file,
+                find_key_position(&content, product, None, equivalences_line),
+                format!("equivalence '{}' has no roadmap in '{}'", product, config.roadmap_folder),
+            ));
+        }
+    }
+
+    (Some(config), diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Unknown keys and values of a configuration, checked against the real schema
+    fn unknown_keys(content: &str) -> Vec<ConfigDiagnostic> {
+        let schema: JsonValue = serde_json::from_str(CONFIG_SCHEMA).unwrap();
+        let yaml: YamlValue = serde_yaml::from_str(content).unwrap();
+        let walk = SchemaWalk { root: &schema, content, file: "configuration.yml" };
+        let mut diagnostics = vec![];
+        check_unknown_keys(&walk, &schema, &yaml, "", 1, &mut diagnostics);
+        diagnostics
+    }
+
+    #[test]
+    fn find_key_position_skips_comments_and_list_markers() {
+        let content = "# platform: github\nplatform: github\njobs:\n  - scan: repo\n    name: my-repo\n";
+        assert_eq!(find_key_position(content, "platform", None, 1), Some((2, 1)));
+        assert_eq!(find_key_position(content, "scan", None, 1), Some((4, 5)));
+        assert_eq!(find_key_position(content, "name", None, 1), Some((5, 5)));
+    }
+
+    #[test]
+    fn find_key_position_matches_the_value_from_a_line() {
+        let content = "sources:\n  - name: a\n  - name: b\nname: c\n";
+        assert_eq!(find_key_position(content, "name", Some("b"), 1), Some((3, 5)));
+        assert_eq!(find_key_position(content, "name", None, 4), Some((4, 1)));
+        assert_eq!(find_key_position(content, "name", Some("d"), 1), None);
+        assert_eq!(find_key_position(content, "nam", None, 1), None);
+    }
+
+    #[test]
+    fn is_close_accepts_typos_and_one_missing_character() {
+        assert!(is_close("platform", "plaftorm"));
+        assert!(is_close("platform", "platfrom"));
+        assert!(is_close("platform", "plaform"));
+        assert!(is_close("platform", "pllatform"));
+        assert!(!is_close("platform", "base_url"));
+        assert!(!is_close("platform", "plat"));
+    }
+
+    #[test]
+    fn unknown_key_with_a_close_name_gets_a_hint() {
+        let diagnostics = unknown_keys("trace_level: info\nplaform: github\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(2), Some(1)));
+        assert_eq!(diagnostics[0].message, "unknown key 'plaform', did you mean 'platform'?");
+    }
+
+    #[test]
+    fn unknown_nested_key_lists_the_expected_ones() {
+        let diagnostics = unknown_keys("http:\n  timeout_secs: 30\n  proxy: none\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!((diagnostics[0].line, diagnostics[0].column), (Some(3), Some(3)));
+        assert!(diagnostics[0].message.starts_with("unknown key 'http.proxy' (expected one of: "));
+        assert!(diagnostics[0].message.contains("timeout_secs"));
+    }
+
+    #[test]
+    fn unsupported_value_is_reported_at_its_key() {
+        let diagnostics = unknown_keys("platform: gitlab\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(1));
+        assert!(diagnostics[0].message.starts_with("unsupported value 'gitlab' for 'platform'"));
+    }
+}
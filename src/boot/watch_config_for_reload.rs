@@ -1,42 +1,97 @@
-
 use notify::{Watcher, EventKind, RecommendedWatcher, RecursiveMode, Config};
-use std::sync::mpsc::channel;
-use std::error::Error;
-use std::sync::{Arc, Mutex};
-use std::path::Path;
+use std::collections::HashSet;
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::path::{Path, PathBuf};
 use std::thread;
-use std::env;
+use std::time::Duration;
+use tokio::runtime::Handle;
+
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::boot::load_config::reload_config;
+use crate::boot::open_tenant_databases::open_tenant_databases;
+use crate::boot::shared_config::SharedConfig;
+use crate::boot::validate_config::{has_errors, validate_config};
+use crate::roadmap::process_yaml_files::process_yaml_files;
+use crate::roadmap::reload_roadmaps::reload_roadmaps;
+use crate::models::{AppConfig, ConfigOverrides};
+use crate::types::MyError;
 
-use crate::models::AppConfig;
-use crate::boot::load_config::load_config;
+// Editors write a file in several steps, changes are applied once it is quiet
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+#[derive(Default)]
+struct PendingChanges {
+    config: bool,
+    roadmap_folders: HashSet<PathBuf>,
+}
 
 // Set up hot-reload using a file watcher
-pub fn watch_config_for_reload(shared_config: Arc<Mutex<AppConfig>>) -> Result<(), Box<dyn Error>> {
-    let config_file_path = "configuration.yml";
+pub fn watch_config_for_reload(
+    shared_config: SharedConfig,
+    config_file_path: String,
+    overrides: ConfigOverrides,
+    runtime: Handle,
+) -> Result<(), MyError> {
     let (tx, rx) = channel();
+    let config_path = absolute(Path::new(&config_file_path));
+    let roadmap_folders: HashSet<PathBuf> = configs(&shared_config.current())
+        .map(|config| absolute(Path::new(&config.roadmap_folder)))
+        .collect();
+
+    // Watch the parent folder: editors replace the file, which drops a watch on the file itself
+    let mut watcher = RecommendedWatcher::new(tx, Config::default())?;
+    let config_dir = config_path.parent().map(Path::to_path_buf).unwrap_or_else(|| PathBuf::from("."));
+    watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+    for roadmap_folder in &roadmap_folders {
+        watcher.watch(roadmap_folder, RecursiveMode::NonRecursive)?;
+        tracing::info!("Watching {} for changes", roadmap_folder.display());
+    }
+    tracing::info!("Watching {} for changes", config_path.display());
 
     // Move the watcher into a new thread to keep it alive
     thread::spawn(move || {
-        let mut watcher = RecommendedWatcher::new(tx, Config::default()).expect("Failed to create watcher");
-        watcher.watch(Path::new(config_file_path), RecursiveMode::NonRecursive).expect("Failed to watch file");
+        let _watcher = watcher;
+        let mut pending = PendingChanges::default();
 
         loop {
-            match rx.recv() {
+            let timeout = if pending.config || !pending.roadmap_folders.is_empty() { DEBOUNCE } else { Duration::from_secs(3600) };
+            match rx.recv_timeout(timeout) {
                 Ok(Ok(event)) => {
-                    match event.kind {
-                        EventKind::Modify(_) | EventKind::Create(_) => {
-                            if let Err(e) = load_and_update_config(config_file_path, &shared_config) {
-                                tracing::error!("Failed to reload config: {}", e);
+                    if !matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_)) {
+                        continue;
+                    }
+                    for path in &event.paths {
+                        let path = absolute(path);
+                        if path == config_path {
+                            pending.config = true;
+                        } else if let Some(folder) = path.parent().filter(|folder| roadmap_folders.contains(*folder)) {
+                            if path.extension().and_then(|s| s.to_str()) == Some("yml") {
+                                pending.roadmap_folders.insert(folder.to_path_buf());
                             }
                         }
-                        _ => {} // Ignore other events
                     }
                 }
                 Ok(Err(e)) => {
                     tracing::error!("File event error: {:?}", e);
                 }
-                Err(e) => {
-                    tracing::error!("Channel receive error: {:?}", e);
+                Err(RecvTimeoutError::Timeout) => {
+                    let changes = std::mem::take(&mut pending);
+                    if changes.config {
+                        reload_configuration(&config_file_path, &overrides, &shared_config, &roadmap_folders, &runtime);
+                    }
+                    // Every database loaded from a changed folder, tenants included
+                    let current = shared_config.current();
+                    for config in configs(&current) {
+                        if !changes.roadmap_folders.contains(&absolute(Path::new(&config.roadmap_folder))) {
+                            continue;
+                        }
+                        if let Err(e) = runtime.block_on(reload_roadmaps(config)) {
+                            tracing::error!("Failed to reload the roadmap of {}: {}", tenant_name(config), e);
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    tracing::error!("Configuration watcher stopped");
                     break; // Exit the loop if there's an error
                 }
             }
@@ -46,26 +101,76 @@ pub fn watch_config_for_reload(shared_config: Arc<Mutex<AppConfig>>) -> Result<(
     Ok(())
 }
 
-fn load_and_update_config(config_file_path: &'static str, shared_config: &Arc<Mutex<AppConfig>>) -> Result<(), Box<dyn Error>> {
-    log_working_directory(); 
-    // Check if the file still exists
-    if !Path::new(config_file_path).exists() {
-        return Err(format!("Configuration file '{}' not found.", config_file_path).into());
-    }
+// Top level configuration and the configuration of each tenant
+fn configs(config: &AppConfig) -> impl Iterator<Item = &AppConfig> {
+    std::iter::once(config).chain(config.tenants.values())
+}
 
-    let new_config = load_config(config_file_path)?;
-    let mut config = shared_config.lock().unwrap();
-    *config = new_config;
-    tracing::info!("Configuration reloaded.");
-    Ok(())
+fn tenant_name(config: &AppConfig) -> String {
+    config.tenant.as_ref().map(|tenant| format!("tenant '{}'", tenant)).unwrap_or_else(|| "the top level".to_string())
 }
 
+// An invalid file keeps the running configuration
+fn reload_configuration(
+    config_file_path: &str,
+    overrides: &ConfigOverrides,
+    shared_config: &SharedConfig,
+    roadmap_folders: &HashSet<PathBuf>, // Watched since the start
+    runtime: &Handle,
+) {
+    let (_, diagnostics) = validate_config(config_file_path);
+    for diagnostic in &diagnostics {
+        tracing::warn!("{}", diagnostic);
+    }
+    if has_errors(&diagnostics) {
+        tracing::error!("Configuration not reloaded, {} is invalid", config_file_path);
+        return;
+    }
+
+    match reload_config(config_file_path, overrides) {
+        Ok(mut new_config) => {
+            let current = shared_config.current();
+            // The menu started with `--tenant` keeps its tenant
+            if let Some(tenant) = &current.tenant {
+                match config_for_tenant(&new_config, tenant) {
+                    Ok(tenant_config) => new_config = tenant_config.clone(),
+                    Err(e) => return tracing::error!("Configuration not reloaded: {}", e),
+                }
+            }
 
-fn log_working_directory() {
-    match env::current_dir() {
-        Ok(path) => tracing::info!("Current working directory: {}", path.display()),
-        Err(e) => tracing::error!("Failed to get current working directory: {}", e),
+            // The databases, HTTP counters and circuit breakers outlive the configuration
+            new_config.db = current.db.clone();
+            new_config.http_stats = current.http_stats.clone();
+            new_config.circuit_breakers = current.circuit_breakers.clone();
+            for (name, tenant) in new_config.tenants.iter_mut() {
+                tenant.db = current.tenants.get(name).and_then(|current| current.db.clone());
+                tenant.http_stats = current.http_stats.clone();
+                tenant.circuit_breakers = current.circuit_breakers.clone();
+            }
+            // Tenants added by the reload get their database and roadmap
+            match open_tenant_databases(&mut new_config) {
+                Ok(opened) => {
+                    for name in opened {
+                        let tenant = &new_config.tenants[&name];
+                        if let Err(e) = runtime.block_on(process_yaml_files(tenant, &tenant.roadmap_folder)) {
+                            tracing::error!("Failed to load the roadmap of tenant '{}': {}", name, e);
+                        }
+                    }
+                }
+                Err(e) => return tracing::error!("Configuration not reloaded: {}", e),
+            }
+            for config in configs(&new_config) {
+                if !roadmap_folders.contains(&absolute(Path::new(&config.roadmap_folder))) {
+                    tracing::warn!("roadmap_folder of {} changed, restart to watch {}", tenant_name(config), config.roadmap_folder);
+                }
+            }
+            shared_config.replace(new_config);
+            tracing::info!("Configuration reloaded.");
+        }
+        Err(e) => tracing::error!("Failed to reload config: {}", e),
     }
 }
 
-
+fn absolute(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,140 @@
+pub mod run_scan;
+pub mod run_jobs;
+pub mod run_roadmap;
+pub mod print_policy_report;
+pub mod serve;
+
+use std::fmt;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{ConfigOverrides, JobConfig, JobScope};
+
+// Exit code of `scan` and `report` when a policy threshold is breached
+// (1 is any other failure, 2 a usage error reported by clap)
+pub const EXIT_POLICY_BREACHED: i32 = 3;
+
+/// Technical debt of the repositories against the roadmaps.
+/// Without a command, the interactive menu is started in a terminal, the configured `jobs` are run otherwise.
+#[derive(Debug, Parser)]
+#[command(name = "xdebt", version)]
+pub struct Cli {
+    /// Configuration file
+    #[arg(short, long, global = true, default_value = "configuration.yml")]
+    pub config: String,
+
+    /// Folder of the JSON results, instead of `output_folder`
+    #[arg(short, long, global = true)]
+    pub output_folder: Option<String>,
+
+    /// What the commands print on stdout
+    #[arg(short, long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+
+    /// Repositories analysed at the same time, instead of `concurrency`
+    #[arg(short = 'j', long, global = true)]
+    pub concurrency: Option<usize>,
+
+    /// Configuration of this tenant instead of the top level one, `serve` takes it from each request
+    #[arg(short, long, global = true)]
+    pub tenant: Option<String>,
+
+    /// Log level, instead of `trace_level`
+    #[arg(short, long, global = true, value_parser = ["trace", "debug", "info", "warn", "error"])]
+    pub log_level: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+impl Cli {
+    pub fn overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            trace_level: self.log_level.clone(),
+            output_folder: self.output_folder.clone(),
+            concurrency: self.concurrency,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Analyse repositories and write the results in the output folder
+    Scan {
+        #[command(subcommand)]
+        target: ScanTarget,
+    },
+    /// Roadmap database
+    Roadmap {
+        #[command(subcommand)]
+        command: RoadmapCommand,
+    },
+    /// Check the results of the last scan against the policy thresholds
+    Report {
+        /// Results of this project instead of all_projects.json
+        #[arg(short, long)]
+        project: Option<String>,
+    },
+    /// Run the `jobs` of the configuration and exit, as done without a terminal
+    Run,
+    /// gRPC server (and REST API when enabled) with configuration hot-reload
+    Serve,
+    /// Check the configuration file, base URLs included
+    Validate,
+    /// `xdebt <repository>`, same as `xdebt scan repo <repository>`
+    #[command(external_subcommand)]
+    Repository(Vec<String>),
+}
+
+// Persisted with the queued jobs of the server
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "scan", rename_all = "lowercase")]
+pub enum ScanTarget {
+    /// One repository, looked up in every project of every source
+    Repo { name: String },
+    /// Every repository of a project (Bitbucket key, GitHub organisation or team, workspace)
+    Project { name: String },
+    /// Every repository of every source
+    All,
+}
+
+impl fmt::Display for ScanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScanTarget::Repo { name } => write!(f, "repo {}", name),
+            ScanTarget::Project { name } => write!(f, "project {}", name),
+            ScanTarget::All => write!(f, "all"),
+        }
+    }
+}
+
+impl From<&JobConfig> for ScanTarget {
+    fn from(job: &JobConfig) -> Self {
+        let name = job.name.clone().unwrap_or_default();
+        match job.scan {
+            JobScope::Repo => ScanTarget::Repo { name },
+            JobScope::Project => ScanTarget::Project { name },
+            JobScope::All => ScanTarget::All,
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RoadmapCommand {
+    /// Replace the roadmap database with the files of a folder
+    Import {
+        /// Instead of `roadmap_folder`
+        folder: Option<String>,
+    },
+    /// Roadmap of a product, or the list of products
+    Show { product: Option<String> },
+    /// Products whose name or equivalences contain the text
+    Search { text: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+}
@@ -0,0 +1,21 @@
+use crate::cli::OutputFormat;
+use crate::models::PolicyReport;
+use crate::types::MyError;
+
+pub fn print_policy_report(report: &PolicyReport, format: OutputFormat) -> Result<(), MyError> {
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(report)?),
+        OutputFormat::Text => {
+            println!(
+                "{} repositories: {} outdated, {} non compliant, {} failed",
+                report.repositories, report.outdated, report.non_compliant, report.failed
+            );
+            if report.breaches.is_empty() {
+                println!("Policy respected");
+            } else {
+                println!("Policy breached: {}", report.breaches.join(", "));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+use serde_json::Value;
+
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::cli::ScanTarget;
+use crate::cli::run_scan::run_scan;
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Headless run of the `jobs` of the configuration, results of every job are merged by project.
+// A failing job does not stop the next ones, the run fails at the end.
+// A job with a `tenant` runs with its configuration; with `--tenant`, the jobs of the other tenants are skipped.
+pub async fn run_jobs(config: &AppConfig) -> Result<HashMap<String, Vec<Value>>, MyError> {
+    if config.jobs.is_empty() {
+        return Err(CustomError::config(
+            "No `jobs` configured: without a terminal, use `xdebt scan ...`, `xdebt serve` or declare `jobs`",
+        ));
+    }
+
+    let mut all_results: HashMap<String, Vec<Value>> = HashMap::new();
+    let mut failed_jobs = 0;
+
+    for job in &config.jobs {
+        let target = ScanTarget::from(job);
+        let job_config = match job.tenant.as_deref() {
+            Some(tenant) if config.tenant.as_deref() == Some(tenant) => config,
+            Some(tenant) if config.tenant.is_some() => {
+                tracing::info!("Skipping job {} of tenant '{}'", target, tenant);
+                continue;
+            }
+            Some(tenant) => match config_for_tenant(config, tenant) {
+                Ok(tenant_config) => tenant_config,
+                Err(e) => {
+                    tracing::error!("Job {} failed: {}", target, e);
+                    failed_jobs += 1;
+                    continue;
+                }
+            },
+            None => config,
+        };
+
+        tracing::info!("Running job {}", target);
+        match run_scan(job_config, &target).await {
+            Ok(results) => {
+                for (project, reports) in results {
+                    all_results.entry(project).or_default().extend(reports);
+                }
+            }
+            Err(e) => {
+                tracing::error!("Job {} failed: {}", target, e);
+                failed_jobs += 1;
+            }
+        }
+    }
+
+    if failed_jobs > 0 {
+        return Err(CustomError::project_error(format!("{} of {} jobs failed", failed_jobs, config.jobs.len())));
+    }
+    Ok(all_results)
+}
@@ -0,0 +1,58 @@
+use crate::cli::{OutputFormat, RoadmapCommand};
+use crate::models::{AppConfig, Roadmap};
+use crate::roadmap::reload_roadmaps::reload_roadmaps;
+use crate::services::get_roadmap::get_roadmap;
+use crate::services::list_roadmaps::list_roadmaps;
+use crate::services::search_roadmaps::search_roadmaps;
+use crate::types::{CustomError, MyError};
+
+pub async fn run_roadmap(config: &AppConfig, command: &RoadmapCommand, format: OutputFormat) -> Result<(), MyError> {
+    let db = config.db.as_ref().ok_or_else(|| CustomError::database_error("Database is not initialized"))?;
+
+    match command {
+        RoadmapCommand::Import { folder } => {
+            let mut config = config.clone();
+            if let Some(folder) = folder {
+                config.roadmap_folder = folder.clone();
+            }
+            reload_roadmaps(&config).await?;
+            println!("Roadmap imported from {}", config.roadmap_folder);
+        }
+        RoadmapCommand::Show { product: Some(product) } => {
+            let roadmap = get_roadmap(db, product)?
+                .ok_or_else(|| CustomError::not_found(format!("No roadmap for product '{}'", product)))?;
+            match format {
+                OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&roadmap)?),
+                OutputFormat::Text => print_roadmap(&roadmap),
+            }
+        }
+        RoadmapCommand::Show { product: None } => print_products(&list_roadmaps(db)?, format)?,
+        RoadmapCommand::Search { text } => print_products(&search_roadmaps(config, db, text)?, format)?,
+    }
+    Ok(())
+}
+
+fn print_products(roadmaps: &[Roadmap], format: OutputFormat) -> Result<(), MyError> {
+    let products: Vec<&str> = roadmaps.iter().map(|roadmap| roadmap.product.as_str()).collect();
+    match format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&products)?),
+        OutputFormat::Text => products.iter().for_each(|product| println!("{}", product)),
+    }
+    Ok(())
+}
+
+fn print_roadmap(roadmap: &Roadmap) {
+    let date = |date: Option<chrono::NaiveDate>| date.map(|d| d.to_string()).unwrap_or_else(|| "-".to_string());
+    println!("{}", roadmap.product);
+    println!("{:<12} {:<12} {:<12} {:<12} source", "cycle", "release", "eol", "extended");
+    for entry in &roadmap.entries {
+        println!(
+            "{:<12} {:<12} {:<12} {:<12} {}",
+            entry.cycle,
+            date(entry.release_date),
+            date(entry.eol),
+            date(entry.extended_end_date),
+            entry.source_name.as_deref().unwrap_or("-"),
+        );
+    }
+}
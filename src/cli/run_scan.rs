@@ -0,0 +1,22 @@
+use std::collections::HashMap;
+use serde_json::Value;
+
+use crate::cli::ScanTarget;
+use crate::models::AppConfig;
+use crate::services::analyze_all_repositories::analyze_all_repositories;
+use crate::services::analyze_specific_project::analyze_specific_project;
+use crate::services::analyze_specific_repository::analyze_specific_repository;
+use crate::types::MyError;
+use crate::utils::http_cache::prune_http_cache;
+
+// Same analyses as the menu, without prompts
+pub async fn run_scan(config: &AppConfig, target: &ScanTarget) -> Result<HashMap<String, Vec<Value>>, MyError> {
+    if let Err(e) = prune_http_cache(config) {
+        tracing::warn!("HTTP cache not pruned: {}", e);
+    }
+    match target {
+        ScanTarget::Repo { name } => analyze_specific_repository(config, Some(name)).await,
+        ScanTarget::Project { name } => analyze_specific_project(config, Some(name)).await,
+        ScanTarget::All => analyze_all_repositories(config).await,
+    }
+}
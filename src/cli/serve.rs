@@ -0,0 +1,65 @@
+use crate::boot::shared_config::SharedConfig;
+use crate::boot::watch_config_for_reload::watch_config_for_reload;
+use crate::display_menu::display_menu;
+use crate::grpc_server::start_grpc_server;
+use crate::jobs::job_queue::JobQueue;
+use crate::messaging::start_consumers::start_consumers;
+use crate::rest_server::start_rest_server;
+use crate::models::{AppConfig, ConfigOverrides};
+use crate::types::MyError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServeMode {
+    Menu,
+    Grpc,
+}
+
+// Interactive menu or gRPC server (with the REST API and Iggy consumers when enabled), following the configuration reloads
+pub async fn serve(
+    config: AppConfig,
+    config_file_path: &str,
+    overrides: ConfigOverrides,
+    mode: ServeMode,
+) -> Result<(), MyError> {
+    // Swapped on reload, every request or menu action takes the current snapshot
+    let shared_config = SharedConfig::new(config);
+    if let Err(e) = watch_config_for_reload(
+        shared_config.clone(),
+        config_file_path.to_string(),
+        overrides,
+        tokio::runtime::Handle::current(),
+    ) {
+        tracing::error!("Hot-reload disabled, cannot watch the configuration: {}", e);
+    }
+
+    match mode {
+        ServeMode::Grpc => {
+            // Analyses of the clients and of the `schedules`, run one after the other
+            let queue = JobQueue::open(shared_config)?;
+            queue.start();
+            let rest = queue.config().rest.clone();
+            if rest.enabled {
+                let rest_queue = queue.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = start_rest_server(&rest.address, rest_queue).await {
+                        tracing::error!("REST server failed: {}", e);
+                    }
+                });
+            }
+            // Requests of the Iggy streams, queued like the gRPC ones
+            if queue.config().iggy.enabled {
+                start_consumers(queue.clone());
+            }
+            if let Err(e) = start_grpc_server(queue).await {
+                return Err(format!("gRPC server failed: {}", e).into());
+            }
+        }
+        // Run display menu in a loop, it exits the process on its own
+        ServeMode::Menu => loop {
+            let _ = display_menu(&shared_config.current()).await;
+            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        },
+    }
+
+    Ok(())
+}
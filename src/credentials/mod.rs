@@ -0,0 +1,55 @@
+pub mod read_netrc;
+pub mod resolve_credentials;
+
+use std::fmt;
+
+use base64::{engine::general_purpose, Engine as _};
+use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
+
+use crate::types::MyError;
+
+// A password or token, never printed by Debug so it stays out of the traces
+#[derive(Clone)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn new<T: Into<String>>(value: T) -> Self {
+        Secret(value.into())
+    }
+
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Secret(***)")
+    }
+}
+
+/// Credentials of a source, resolved once at boot
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    Anonymous,
+    Basic { username: String, password: Secret },
+    Bearer(Secret), // Personal access token, OAuth or HTTP access token
+}
+
+impl Credentials {
+    // Authorization header flagged as sensitive, reqwest then redacts it from its debug output
+    pub fn authorization_header(&self) -> Result<Option<(HeaderName, HeaderValue)>, MyError> {
+        let value = match self {
+            Credentials::Anonymous => return Ok(None),
+            Credentials::Basic { username, password } => format!(
+                "Basic {}",
+                general_purpose::STANDARD.encode(format!("{}:{}", username, password.expose()))
+            ),
+            Credentials::Bearer(token) => format!("Bearer {}", token.expose()),
+        };
+
+        let mut header = HeaderValue::from_str(&value)?;
+        header.set_sensitive(true);
+        Ok(Some((AUTHORIZATION, header)))
+    }
+}
@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tracing::debug;
+
+use crate::credentials::{Credentials, Secret};
+use crate::types::{CustomError, MyError};
+
+// ~/.netrc unless another path is given
+fn netrc_path(path: Option<&str>) -> PathBuf {
+    match path {
+        Some(path) => PathBuf::from(path),
+        None => PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".netrc"),
+    }
+}
+
+/// Login and password of `host` in a netrc file, the `default` entry being the fallback
+pub fn read_netrc(path: Option<&str>, host: &str) -> Result<Credentials, MyError> {
+    let path = netrc_path(path);
+    let content = fs::read_to_string(&path)
+        .map_err(|e| CustomError::auth(format!("Cannot read netrc file {}: {}", path.display(), e)))?;
+
+    // Entries are whitespace separated tokens: machine <host> login <user> password <secret>
+    let mut tokens = content.split_whitespace();
+    let mut entries: Vec<(String, Option<String>, Option<String>)> = vec![];
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "machine" => entries.push((tokens.next().unwrap_or_default().to_string(), None, None)),
+            "default" => entries.push((String::new(), None, None)),
+            "login" | "password" => {
+                let value = tokens.next().map(str::to_string);
+                if let Some(entry) = entries.last_mut() {
+                    if token == "login" { entry.1 = value } else { entry.2 = value }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let entry = entries
+        .iter()
+        .find(|(machine, _, _)| machine == host)
+        .or_else(|| entries.iter().find(|(machine, _, _)| machine.is_empty()));
+
+    match entry {
+        Some((machine, Some(login), Some(password))) => {
+            debug!("Credentials of {} found in {} (entry '{}')", host, path.display(), machine);
+            Ok(Credentials::Basic { username: login.clone(), password: Secret::new(password.clone()) })
+        }
+        _ => Err(CustomError::auth(format!("No login/password for {} in {}", host, path.display()))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Netrc file of a test in the temporary folder, removed once read
+    fn read(name: &str, content: &str, host: &str) -> Result<Credentials, MyError> {
+        let path = std::env::temp_dir().join(format!("xdebt-netrc-{}-{}", std::process::id(), name));
+        fs::write(&path, content).unwrap();
+        let credentials = read_netrc(path.to_str(), host);
+        fs::remove_file(&path).unwrap();
+        credentials
+    }
+
+    fn basic(credentials: Credentials) -> (String, String) {
+        match credentials {
+            Credentials::Basic { username, password } => (username, password.expose().to_string()),
+            _ => panic!("basic credentials expected"),
+        }
+    }
+
+    #[test]
+    fn machine_entry_of_the_host() {
+        let content = "machine gitlab.com login other password x\nmachine github.com\n  login me\n  password secret\n";
+        assert_eq!(basic(read("machine", content, "github.com").unwrap()), ("me".to_string(), "secret".to_string()));
+    }
+
+    #[test]
+    fn default_entry_when_the_host_is_missing() {
+        let content = "machine gitlab.com login other password x\ndefault login me password secret\n";
+        assert_eq!(basic(read("default", content, "github.com").unwrap()), ("me".to_string(), "secret".to_string()));
+    }
+
+    #[test]
+    fn entry_without_password_is_an_error() {
+        assert!(read("incomplete", "machine github.com login me\n", "github.com").is_err());
+        assert!(read("other", "machine gitlab.com login me password secret\n", "github.com").is_err());
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        assert!(read_netrc(Some("/nonexistent/.netrc"), "github.com").is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::process::Command;
+
+use dotenv::dotenv;
+use tracing::info;
+
+use crate::credentials::read_netrc::read_netrc;
+use crate::credentials::{Credentials, Secret};
+use crate::models::{CredentialsConfig, SourceConfig};
+use crate::types::{CustomError, MyError};
+
+// Variables read by default: (token, username, password)
+fn default_env_vars(platform: &str) -> (&'static str, Option<&'static str>, Option<&'static str>) {
+    match platform {
+        "bitbucket-cloud" => ("BITBUCKET_CLOUD_TOKEN", Some("BITBUCKET_CLOUD_USERNAME"), Some("BITBUCKET_CLOUD_APP_PASSWORD")),
+        "github" => ("GITHUB_TOKEN", None, None),
+        _ => ("BITBUCKET_TOKEN", Some("BITBUCKET_USERNAME"), Some("BITBUCKET_PASSWORD")),
+    }
+}
+
+// Explicit variable names are used as is, default ones get the source `env_prefix`
+fn var_name(source: &SourceConfig, explicit: &Option<String>, default: Option<&str>) -> Option<String> {
+    explicit.clone().or_else(|| {
+        default.map(|name| match &source.env_prefix {
+            Some(prefix) => format!("{}_{}", prefix, name),
+            None => name.to_string(),
+        })
+    })
+}
+
+// A token wins over a username and password
+fn from_variables(
+    source: &SourceConfig,
+    names: (&Option<String>, &Option<String>, &Option<String>),
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<Credentials, MyError> {
+    let (default_token, default_username, default_password) = default_env_vars(&source.platform);
+    let token_var = var_name(source, names.0, Some(default_token));
+    let username_var = var_name(source, names.1, default_username);
+    let password_var = var_name(source, names.2, default_password);
+
+    if let Some(token) = token_var.as_deref().and_then(&lookup) {
+        return Ok(Credentials::Bearer(Secret::new(token)));
+    }
+
+    let username = username_var.as_deref().and_then(&lookup);
+    let password = password_var.as_deref().and_then(&lookup);
+    match (username, password) {
+        (Some(username), Some(password)) => Ok(Credentials::Basic { username, password: Secret::new(password) }),
+        _ => {
+            let mut expected = token_var.unwrap_or_default();
+            if let (Some(username_var), Some(password_var)) = (username_var, password_var) {
+                expected.push_str(&format!(", or {} and {}", username_var, password_var));
+            }
+            Err(CustomError::auth(format!("Missing credentials for source '{}', set {}", source.name, expected)))
+        }
+    }
+}
+
+// KEY=value lines, `export`, quotes and comments allowed
+fn read_dotenv(path: &str) -> Result<HashMap<String, String>, MyError> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| CustomError::auth(format!("Cannot read {}: {}", path, e)))?;
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.trim_start_matches("export ").split_once('='))
+        .map(|(key, value)| {
+            let value = value.trim().trim_matches('"').trim_matches('\'');
+            (key.trim().to_string(), value.to_string())
+        })
+        .collect())
+}
+
+// Token alone, or password of `username`
+fn from_secret(username: &Option<String>, secret: String) -> Credentials {
+    match username {
+        Some(username) => Credentials::Basic { username: username.clone(), password: Secret::new(secret) },
+        None => Credentials::Bearer(Secret::new(secret)),
+    }
+}
+
+/// Resolve the credentials of a source through its provider (environment by default).
+/// Called once at boot, requests only read the resolved value.
+pub fn resolve_credentials(source: &SourceConfig) -> Result<Credentials, MyError> {
+    let default_provider = CredentialsConfig::Env { token_env: None, username_env: None, password_env: None };
+    let provider = source.credentials.as_ref().unwrap_or(&default_provider);
+
+    let credentials = match provider {
+        CredentialsConfig::Env { token_env, username_env, password_env } => {
+            dotenv().ok(); // Load environment variables from .env file
+            from_variables(source, (token_env, username_env, password_env), |name| env::var(name).ok())?
+        }
+        CredentialsConfig::Dotenv { path, token_env, username_env, password_env } => {
+            // Read without exporting the variables to the process environment
+            let variables = read_dotenv(path)?;
+            from_variables(source, (token_env, username_env, password_env), |name| variables.get(name).cloned())?
+        }
+        CredentialsConfig::Netrc { path } => {
+            let host = reqwest::Url::parse(&source.base_url)
+                .ok()
+                .and_then(|url| url.host_str().map(str::to_string))
+                .ok_or_else(|| CustomError::config(format!("Invalid base_url '{}'", source.base_url)))?;
+            read_netrc(path.as_deref(), &host)?
+        }
+        CredentialsConfig::File { path, username } => {
+            let secret = fs::read_to_string(path)
+                .map_err(|e| CustomError::auth(format!("Cannot read secret file {}: {}", path, e)))?;
+            from_secret(username, secret.trim().to_string())
+        }
+        CredentialsConfig::Command { credential_command, username } => {
+            let output = Command::new("sh")
+                .arg("-c")
+                .arg(credential_command)
+                .output()
+                .map_err(|e| CustomError::auth(format!("Cannot run credential_command of source '{}': {}", source.name, e)))?;
+            if !output.status.success() {
+                return Err(CustomError::auth(format!(
+                    "credential_command of source '{}' failed with {}",
+                    source.name, output.status
+                )));
+            }
+            from_secret(username, String::from_utf8_lossy(&output.stdout).trim().to_string())
+        }
+    };
+
+    let kind = match &credentials {
+        Credentials::Anonymous => "anonymous",
+        Credentials::Basic { .. } => "basic",
+        Credentials::Bearer(_) => "token",
+    };
+    info!("Credentials of source '{}' resolved ({})", source.name, kind);
+    Ok(credentials)
+}
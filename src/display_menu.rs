@@ -12,7 +12,7 @@ pub async fn display_menu(config: &AppConfig) -> Result<(), Box<dyn Error + Send
 
     // Define the menu text and options
     let menu_text = "Selectionne une     let cloned_config = config1.clone();option:";
-    let menu_options = vec![
+    let menu_options = [
         "1. Analyser une application (GPECS, XCAD...etc)",
         "2. Analyser un domaine entier (SES, PTEP...etc)",
         "3. Analyser toute les applications",
@@ -31,7 +31,7 @@ pub async fn display_menu(config: &AppConfig) -> Result<(), Box<dyn Error + Send
             let _ = analyze_specific_repository(config, None).await;
         }
         "2" => {
-            let _ = analyze_specific_project(config).await;
+            let _ = analyze_specific_project(config, None).await;
         }
         "3" => {
             let _ = analyze_all_repositories(config).await;
@@ -41,6 +41,7 @@ pub async fn display_menu(config: &AppConfig) -> Result<(), Box<dyn Error + Send
         }
         "5" => {
             tracing::info!("Exiting...");
+            db.flush_async().await?;
             std::process::exit(0);
         }
         _ => {
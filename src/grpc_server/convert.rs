@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use serde_json::Value;
+use tonic::Status;
+
+use crate::grpc_server::analyze;
+use crate::jobs::{Job, JobState, ScanEvent};
+use crate::models::{
+    AnalysisError, AnalysisStatus, Evidence, KPIResult, KPIStatus, ProductVerdict, RepositoryReport, Roadmap,
+    RoadmapEntry,
+};
+use crate::types::{error_chain, CustomError, MyError};
+
+// Error categories mapped to the closest gRPC codes
+pub fn to_status(err: MyError) -> Status {
+    status_of(&err)
+}
+
+// Errors shared by the requests waiting for the same job
+pub fn status_of(err: &MyError) -> Status {
+    let message = error_chain(err.as_ref());
+    match CustomError::root(err.as_ref()) {
+        Some(CustomError::NotFound(_)) => Status::not_found(message),
+        Some(CustomError::InvalidInput(_)) => Status::invalid_argument(message),
+        Some(CustomError::Auth(_)) => Status::permission_denied(message),
+        Some(CustomError::CircuitOpen { .. }) => Status::unavailable(message),
+        _ => Status::internal(message),
+    }
+}
+
+// Results are kept as the JSON written to the output folder, the typed reports are read back from it
+pub fn to_project_results(results: HashMap<String, Vec<Value>>) -> Result<Vec<analyze::ProjectResult>, MyError> {
+    let mut projects = results
+        .into_iter()
+        .map(|(project, reports)| {
+            let repositories = reports
+                .into_iter()
+                .map(|report| {
+                    let report: RepositoryReport = serde_json::from_value(report)
+                        .map_err(|e| CustomError::parse(format!("report of project {}", project), e))?;
+                    Ok(report.into())
+                })
+                .collect::<Result<Vec<analyze::RepositoryReport>, MyError>>()?;
+            Ok(analyze::ProjectResult { project, repositories })
+        })
+        .collect::<Result<Vec<_>, MyError>>()?;
+    projects.sort_by(|a, b| a.project.cmp(&b.project));
+    Ok(projects)
+}
+
+impl From<AnalysisStatus> for analyze::AnalysisStatus {
+    fn from(status: AnalysisStatus) -> Self {
+        match status {
+            AnalysisStatus::Complete => analyze::AnalysisStatus::Complete,
+            AnalysisStatus::Incomplete => analyze::AnalysisStatus::Incomplete,
+            AnalysisStatus::Failed => analyze::AnalysisStatus::Failed,
+        }
+    }
+}
+
+impl From<KPIStatus> for analyze::KpiStatus {
+    fn from(status: KPIStatus) -> Self {
+        match status {
+            KPIStatus::Compliant => analyze::KpiStatus::Compliant,
+            KPIStatus::NonCompliant => analyze::KpiStatus::NonCompliant,
+            KPIStatus::UpToDate => analyze::KpiStatus::UpToDate,
+            KPIStatus::Outdated => analyze::KpiStatus::Outdated,
+            KPIStatus::NoActionNeeded => analyze::KpiStatus::NoActionNeeded,
+        }
+    }
+}
+
+impl From<RepositoryReport> for analyze::RepositoryReport {
+    fn from(report: RepositoryReport) -> Self {
+        analyze::RepositoryReport {
+            application: report.application,
+            source: report.source,
+            status: analyze::AnalysisStatus::from(report.status).into(),
+            debt: report.debt.into_iter().map(Into::into).collect(),
+            products: report.products.into_iter().map(Into::into).collect(),
+            errors: report.errors.into_iter().map(Into::into).collect(),
+            head_commit: report.head_commit.unwrap_or_default(),
+            reused: report.reused,
+        }
+    }
+}
+
+impl From<KPIResult> for analyze::KpiResult {
+    fn from(kpi: KPIResult) -> Self {
+        analyze::KpiResult {
+            product: kpi.product,
+            cycle: kpi.cycle,
+            status: analyze::KpiStatus::from(kpi.status).into(),
+            reason: kpi.reason,
+            source: kpi.source.unwrap_or_default(),
+            validity_days: kpi.validity.and_then(|days| days.parse().ok()),
+            finding: Some(kpi.evidence.into()),
+        }
+    }
+}
+
+impl From<Evidence> for analyze::Finding {
+    fn from(evidence: Evidence) -> Self {
+        analyze::Finding {
+            plugin: evidence.plugin,
+            file_path: evidence.file_path.unwrap_or_default(),
+            line: evidence.line.map(|line| line as u32),
+            artifact: evidence.artifact.unwrap_or_default(),
+            matched_alias: evidence.matched_alias.unwrap_or_default(),
+            url: evidence.url.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<ProductVerdict> for analyze::ProductVerdict {
+    fn from(verdict: ProductVerdict) -> Self {
+        analyze::ProductVerdict {
+            product: verdict.product,
+            worst: analyze::KpiStatus::from(verdict.worst).into(),
+            worst_cycle: verdict.worst_cycle,
+            best: analyze::KpiStatus::from(verdict.best).into(),
+            best_cycle: verdict.best_cycle,
+            cycles: verdict.cycles,
+            occurrences: verdict.occurrences as u32,
+        }
+    }
+}
+
+impl From<AnalysisError> for analyze::AnalysisError {
+    fn from(error: AnalysisError) -> Self {
+        analyze::AnalysisError {
+            plugin: error.plugin.unwrap_or_default(),
+            kind: error.kind,
+            message: error.message,
+        }
+    }
+}
+
+impl From<Roadmap> for analyze::Roadmap {
+    fn from(roadmap: Roadmap) -> Self {
+        analyze::Roadmap {
+            product: roadmap.product,
+            domain: roadmap.domain.unwrap_or_default(),
+            chapter: roadmap.chapter.unwrap_or_default(),
+            entries: roadmap.entries.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<RoadmapEntry> for analyze::RoadmapEntry {
+    fn from(entry: RoadmapEntry) -> Self {
+        let date = |date: Option<chrono::NaiveDate>| date.map(|d| d.to_string()).unwrap_or_default();
+        analyze::RoadmapEntry {
+            cycle: entry.cycle,
+            release_date: date(entry.release_date),
+            eol: date(entry.eol),
+            extended_end_date: date(entry.extended_end_date),
+            comment: entry.comment.unwrap_or_default(),
+            source_name: entry.source_name.unwrap_or_default(),
+        }
+    }
+}
+
+impl From<JobState> for analyze::JobState {
+    fn from(state: JobState) -> Self {
+        match state {
+            JobState::Queued => analyze::JobState::Queued,
+            JobState::Running => analyze::JobState::Running,
+            JobState::Completed => analyze::JobState::Completed,
+            JobState::Failed => analyze::JobState::Failed,
+            JobState::Cancelled => analyze::JobState::Cancelled,
+        }
+    }
+}
+
+pub fn to_job_status(job: &Job) -> analyze::JobStatus {
+    analyze::JobStatus {
+        job_id: job.id.clone(),
+        target: job.target.clone(),
+        state: analyze::JobState::from(job.state()).into(),
+        events: job.event_count() as u64,
+    }
+}
+
+pub fn to_scan_event(job_id: &str, sequence: usize, event: ScanEvent) -> analyze::ScanEvent {
+    use analyze::scan_event::Event;
+
+    let event = match event {
+        ScanEvent::JobAccepted { target } => Event::JobAccepted(analyze::JobAccepted { target }),
+        ScanEvent::RepositoryStarted { project, repository } => {
+            Event::RepositoryStarted(analyze::RepositoryStarted { project, repository })
+        }
+        ScanEvent::PluginFinished { repository, plugin, findings, error } => {
+            Event::PluginFinished(analyze::PluginFinished {
+                repository,
+                plugin,
+                findings: findings as u32,
+                error: error.unwrap_or_default(),
+            })
+        }
+        ScanEvent::FindingProduced { repository, plugin, product, cycle, file_path } => {
+            Event::FindingProduced(analyze::FindingProduced {
+                repository,
+                plugin,
+                product,
+                cycle,
+                file_path: file_path.unwrap_or_default(),
+            })
+        }
+        ScanEvent::KpiComputed { repository, product, cycle, status } => Event::KpiComputed(analyze::KpiComputed {
+            repository,
+            product,
+            cycle,
+            status: analyze::KpiStatus::from(status).into(),
+        }),
+        ScanEvent::RepositoryFinished { repository, status } => {
+            Event::RepositoryFinished(analyze::RepositoryFinished {
+                repository,
+                status: analyze::AnalysisStatus::from(status).into(),
+            })
+        }
+        ScanEvent::JobFinished { state, message } => Event::JobFinished(analyze::JobFinished {
+            state: analyze::JobState::from(state).into(),
+            message,
+        }),
+    };
+
+    analyze::ScanEvent {
+        job_id: job_id.to_string(),
+        sequence: sequence as u64,
+        event: Some(event),
+    }
+}
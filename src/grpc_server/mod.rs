@@ -0,0 +1,314 @@
+mod convert;
+mod watch_job;
+
+use crate::auth::authenticate::authenticate;
+use crate::auth::Caller;
+use crate::cli::ScanTarget;
+use crate::jobs::{Job, ScanResults};
+use crate::jobs::job_queue::{JobPriority, JobQueue, ScanRequest};
+use crate::models::{AppConfig, TlsConfig};
+use crate::services::get_roadmap::get_roadmap;
+use crate::services::list_roadmaps::list_roadmaps;
+use crate::services::search_roadmaps::search_roadmaps;
+use crate::types::{CustomError, MyError};
+use convert::{status_of, to_job_status, to_project_results, to_status};
+use watch_job::{watch_job, ScanEventStream};
+
+use std::fs;
+use std::sync::Arc;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
+use tonic::service::Interceptor;
+use tonic::{Request, Response, Status};
+use tonic_reflection::server::Builder as ReflectionBuilder;
+use tracing::{error, info};
+use tracing_futures::Instrument;
+
+pub mod analyze {
+    tonic::include_proto!("analyze"); // Generated from your proto package
+}
+
+#[derive(Debug)]
+pub struct AnalyzeService {
+    queue: JobQueue,
+}
+
+impl AnalyzeService {
+    // Requests of a client go before the scheduled scans, within the tenant and projects it may scan
+    fn enqueue(&self, target: ScanTarget, options: Option<analyze::ScanOptions>, caller: &Caller) -> Result<Arc<Job>, MyError> {
+        caller.config(&self.queue.config())?;
+        let options = options.unwrap_or_default();
+        let request = ScanRequest {
+            target,
+            branch: options.branch,
+            plugins: options.plugins,
+            projects: caller.projects.clone(),
+            tenant: caller.tenant.clone(),
+        };
+        info!(caller = %caller.name, target = %request.label(), "Scan requested");
+        self.queue.enqueue(request, JobPriority::OnDemand, &caller.name)
+    }
+
+    // Finished jobs are kept in memory for a while, they are unknown after a restart. The jobs of other callers and tenants are unknown too
+    fn job(&self, job_id: &str, caller: &Caller) -> Option<Arc<Job>> {
+        self.queue.get(job_id).filter(|job| caller.may_watch(job))
+    }
+
+    // Results of a queued analysis, once its job is over
+    async fn analyze(
+        &self,
+        target: ScanTarget,
+        options: Option<analyze::ScanOptions>,
+        caller: &Caller,
+    ) -> Result<Response<analyze::AnalyzeResponse>, Status> {
+        let job = self.enqueue(target, options, caller).map_err(to_status)?;
+        let label = job.target.clone();
+        match job.results().await {
+            Some(Ok(results)) => analyze_response(&label, (*results).clone()).map(Response::new).map_err(to_status),
+            Some(Err(e)) => {
+                error!(target = %label, error = %e, "Analysis failed");
+                Err(status_of(&e))
+            }
+            None => Err(Status::cancelled(format!("Job {} was cancelled", job.id))),
+        }
+    }
+}
+
+// Set by the token check of every request, a request that did not go through it is refused
+#[allow(clippy::result_large_err)] // The Status of every handler, returned with `?`
+fn caller<T>(request: &Request<T>) -> Result<Caller, Status> {
+    request
+        .extensions()
+        .get::<Caller>()
+        .cloned()
+        .ok_or_else(|| Status::unauthenticated("Request without a checked caller"))
+}
+
+// Bearer token of the `authorization` metadata and tenant of the `tenant` metadata,
+// read from the current configuration so a reload applies to the next requests
+#[derive(Clone)]
+struct TokenCheck {
+    queue: JobQueue,
+}
+
+impl Interceptor for TokenCheck {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        let metadata = |key: &str| request.metadata().get(key).and_then(|value| value.to_str().ok());
+        let caller = authenticate(&self.queue.config().api_tokens, metadata("authorization"), metadata("tenant"))
+            .map_err(|e| Status::unauthenticated(e.to_string()))?;
+        request.extensions_mut().insert(caller);
+        Ok(request)
+    }
+}
+
+fn job_not_found(job_id: &str) -> Status {
+    Status::not_found(format!("No job '{}'", job_id))
+}
+
+fn db(config: &AppConfig) -> Result<&sled::Db, MyError> {
+    config.db.as_ref().ok_or_else(|| CustomError::database_error("Database is not initialized"))
+}
+
+// Typed results of the analysis
+fn analyze_response(target: &str, results: ScanResults) -> Result<analyze::AnalyzeResponse, MyError> {
+    let projects = to_project_results(results)?;
+    info!(target = %target, "Analysis successful");
+    Ok(analyze::AnalyzeResponse {
+        message: format!("{} analyzed successfully", target),
+        projects,
+    })
+}
+
+#[tonic::async_trait]
+impl analyze::analyze_server::Analyze for AnalyzeService {
+    async fn analyze_repository(
+        &self,
+        request: Request<analyze::AnalyzeRequest>,
+    ) -> Result<Response<analyze::AnalyzeResponse>, Status> {
+        let caller = caller(&request)?;
+        let request = request.into_inner();
+        let repo_name = request.repo_name;
+
+        // Log that we received a request
+        info!(repo_name = %repo_name, "Received request to analyze repository");
+
+        // An empty name would be read from stdin
+        if repo_name.is_empty() {
+            return Err(Status::invalid_argument("repo_name is required"));
+        }
+        self.analyze(ScanTarget::Repo { name: repo_name }, request.options, &caller).await
+    }
+
+    async fn analyze_project(
+        &self,
+        request: Request<analyze::AnalyzeProjectRequest>,
+    ) -> Result<Response<analyze::AnalyzeResponse>, Status> {
+        let caller = caller(&request)?;
+        let request = request.into_inner();
+        let project_name = request.project_name;
+        info!(project_name = %project_name, "Received request to analyze project");
+
+        if project_name.is_empty() {
+            return Err(Status::invalid_argument("project_name is required"));
+        }
+        self.analyze(ScanTarget::Project { name: project_name }, request.options, &caller).await
+    }
+
+    async fn analyze_all(
+        &self,
+        request: Request<analyze::AnalyzeAllRequest>,
+    ) -> Result<Response<analyze::AnalyzeResponse>, Status> {
+        info!("Received request to analyze all repositories");
+        let caller = caller(&request)?;
+        self.analyze(ScanTarget::All, request.into_inner().options, &caller).await
+    }
+
+    async fn get_roadmap(
+        &self,
+        request: Request<analyze::GetRoadmapRequest>,
+    ) -> Result<Response<analyze::Roadmap>, Status> {
+        let caller = caller(&request)?;
+        let product = request.into_inner().product;
+        let config = self.queue.config();
+        let config = caller.config(&config).map_err(to_status)?;
+        match db(config).and_then(|db| get_roadmap(db, &product)).map_err(to_status)? {
+            Some(roadmap) => Ok(Response::new(roadmap.into())),
+            None => Err(Status::not_found(format!("No roadmap for product '{}'", product))),
+        }
+    }
+
+    async fn list_products(
+        &self,
+        request: Request<analyze::ListProductsRequest>,
+    ) -> Result<Response<analyze::ListProductsResponse>, Status> {
+        let caller = caller(&request)?;
+        let filter = request.into_inner().filter;
+        let config = self.queue.config();
+        let config = caller.config(&config).map_err(to_status)?;
+        let roadmaps = db(config)
+            .and_then(|db| if filter.is_empty() { list_roadmaps(db) } else { search_roadmaps(config, db, &filter) })
+            .map_err(to_status)?;
+
+        Ok(Response::new(analyze::ListProductsResponse {
+            products: roadmaps.into_iter().map(|roadmap| roadmap.product).collect(),
+        }))
+    }
+
+    async fn get_latest_results(
+        &self,
+        request: Request<analyze::GetLatestResultsRequest>,
+    ) -> Result<Response<analyze::AnalyzeResponse>, Status> {
+        let caller = caller(&request)?;
+        let project = request.into_inner().project;
+        let config = self.queue.config();
+        let project = Some(project.as_str()).filter(|project| !project.is_empty());
+        let projects = caller
+            .load_results(&config, project)
+            .and_then(to_project_results)
+            .map_err(to_status)?;
+
+        Ok(Response::new(analyze::AnalyzeResponse {
+            message: format!("Latest results of {}", project.unwrap_or("all projects")),
+            projects,
+        }))
+    }
+
+    type StartScanStream = ScanEventStream;
+
+    async fn start_scan(
+        &self,
+        request: Request<analyze::StartScanRequest>,
+    ) -> Result<Response<Self::StartScanStream>, Status> {
+        use analyze::start_scan_request::Target;
+
+        let caller = caller(&request)?;
+        let request = request.into_inner();
+        let target = match request.target {
+            Some(Target::RepoName(name)) if !name.is_empty() => ScanTarget::Repo { name },
+            Some(Target::ProjectName(name)) if !name.is_empty() => ScanTarget::Project { name },
+            Some(Target::All(true)) => ScanTarget::All,
+            _ => return Err(Status::invalid_argument("repo_name, project_name or all is required")),
+        };
+        let job = self.enqueue(target, request.options, &caller).map_err(to_status)?;
+        info!(job_id = %job.id, target = %job.target, "Scan queued");
+        Ok(Response::new(watch_job(job, 0)))
+    }
+
+    type WatchJobStream = ScanEventStream;
+
+    async fn watch_job(
+        &self,
+        request: Request<analyze::WatchJobRequest>,
+    ) -> Result<Response<Self::WatchJobStream>, Status> {
+        let caller = caller(&request)?;
+        let request = request.into_inner();
+        let job = self.job(&request.job_id, &caller).ok_or_else(|| job_not_found(&request.job_id))?;
+        Ok(Response::new(watch_job(job, request.from_sequence as usize)))
+    }
+
+    async fn get_job_status(
+        &self,
+        request: Request<analyze::JobRequest>,
+    ) -> Result<Response<analyze::JobStatus>, Status> {
+        let caller = caller(&request)?;
+        let job_id = request.into_inner().job_id;
+        let job = self.job(&job_id, &caller).ok_or_else(|| job_not_found(&job_id))?;
+        Ok(Response::new(to_job_status(&job)))
+    }
+
+    async fn cancel_job(
+        &self,
+        request: Request<analyze::JobRequest>,
+    ) -> Result<Response<analyze::JobStatus>, Status> {
+        let caller = caller(&request)?;
+        let job_id = request.into_inner().job_id;
+        let job = self.job(&job_id, &caller).ok_or_else(|| job_not_found(&job_id))?;
+        let job = self.queue.cancel(&job.id).map_err(to_status)?.ok_or_else(|| job_not_found(&job_id))?;
+        Ok(Response::new(to_job_status(&job)))
+    }
+}
+
+pub async fn start_grpc_server(queue: JobQueue) -> Result<(), Box<dyn std::error::Error>> {
+    let config = queue.config();
+    let addr = config.grpc.address.parse().map_err(|e| format!("Invalid gRPC address '{}': {}", config.grpc.address, e))?;
+
+    // Log server startup information
+    info!(address = %addr, tls = config.grpc.tls.is_some(), callers = config.api_tokens.len(), "Starting gRPC server");
+
+    let mut server = Server::builder();
+    if let Some(tls) = &config.grpc.tls {
+        server = server.tls_config(tls_config(tls).map_err(|e| e.to_string())?)?;
+    }
+
+    let check_token = TokenCheck { queue: queue.clone() };
+    let analyze_service = AnalyzeService { queue };
+
+    let descriptor_set = include_bytes!(concat!(env!("OUT_DIR"), "/analyze_descriptor.bin"));
+    // Add reflection service
+    let reflection_service = ReflectionBuilder::configure()
+        .register_encoded_file_descriptor_set(descriptor_set)
+        .build_v1()?;
+
+    server
+        .add_service(analyze::analyze_server::AnalyzeServer::with_interceptor(analyze_service, check_token))
+        .add_service(reflection_service) // Add reflection to the server
+        .serve(addr)
+        .instrument(tracing::info_span!("grpc_server", addr = %addr)) // Use span to trace the server activity
+        .await?;
+
+    // Log server shutdown
+    info!("gRPC server has been shut down");
+
+    Ok(())
+}
+
+// Server certificate and key, and the CA of the client certificates for mutual TLS
+fn tls_config(tls: &TlsConfig) -> Result<ServerTlsConfig, MyError> {
+    // reqwest and iggy also link aws-lc, rustls needs the provider to be chosen. Already done on a restart
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let read = |path: &str| fs::read(path).map_err(|e| CustomError::config(format!("Cannot read {}: {}", path, e)));
+    let mut tls_config = ServerTlsConfig::new().identity(Identity::from_pem(read(&tls.cert)?, read(&tls.key)?));
+    if let Some(client_ca) = &tls.client_ca {
+        tls_config = tls_config.client_ca_root(Certificate::from_pem(read(client_ca)?));
+    }
+    Ok(tls_config)
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+use tokio::sync::{broadcast, mpsc};
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::Status;
+
+use crate::grpc_server::analyze;
+use crate::grpc_server::convert::to_scan_event;
+use crate::jobs::{Job, ScanEvent};
+
+pub type ScanEventStream = ReceiverStream<Result<analyze::ScanEvent, Status>>;
+
+// Events of a job from `from_sequence`: the history first, then the live ones until the job ends.
+// A watcher lagging behind the live events catches up from the history.
+pub fn watch_job(job: Arc<Job>, from_sequence: usize) -> ScanEventStream {
+    let (sender, receiver) = mpsc::channel(64);
+
+    tokio::spawn(async move {
+        let mut next = from_sequence;
+        loop {
+            let (history, mut live) = job.subscribe();
+            for event in history.into_iter().skip(next) {
+                let finished = matches!(event, ScanEvent::JobFinished { .. });
+                if sender.send(Ok(to_scan_event(&job.id, next, event))).await.is_err() || finished {
+                    return; // Client gone, or job over
+                }
+                next += 1;
+            }
+
+            loop {
+                match live.recv().await {
+                    Ok((sequence, _)) if sequence < next => continue, // Already sent from the history
+                    Ok((sequence, event)) => {
+                        let finished = matches!(event, ScanEvent::JobFinished { .. });
+                        if sender.send(Ok(to_scan_event(&job.id, sequence, event))).await.is_err() || finished {
+                            return;
+                        }
+                        next = sequence + 1;
+                    }
+                    Err(broadcast::error::RecvError::Lagged(_)) => break,
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        }
+    });
+
+    ReceiverStream::new(receiver)
+}
@@ -0,0 +1,9 @@
+use crate::jobs::ScanEvent;
+use crate::models::AppConfig;
+
+// Progress of the job the configuration belongs to, nothing outside of a job
+pub fn emit_event(config: &AppConfig, event: impl FnOnce() -> ScanEvent) {
+    if let Some(job) = &config.job {
+        job.emit(event());
+    }
+}
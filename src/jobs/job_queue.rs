@@ -0,0 +1,294 @@
+use std::sync::{Arc, Mutex};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+
+use crate::boot::config_for_request::config_for_request;
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::boot::shared_config::SharedConfig;
+use crate::cli::ScanTarget;
+use crate::jobs::{Job, JobRegistry};
+use crate::jobs::run_schedules::run_schedules;
+use crate::jobs::run_worker::run_worker;
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Sled tree of the jobs not finished yet, they are queued again after a restart
+const QUEUE_TREE: &str = "job_queue";
+
+// Declared from the highest priority, the next job is the first one in this order then the oldest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobPriority {
+    OnDemand,  // Requested by a client, waiting for the results
+    Scheduled, // Bulk scans of the `schedules`
+}
+
+// What a job scans, two identical requests share the same job
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScanRequest {
+    pub target: ScanTarget,
+    pub branch: String,       // Default branch when empty
+    pub plugins: Vec<String>, // Configured plugins when empty
+    #[serde(default)]
+    pub projects: Vec<String>, // Projects the caller may scan, every project when empty
+    #[serde(default)]
+    pub tenant: String, // Top level configuration when empty
+}
+
+impl ScanRequest {
+    // Target of the job, with its tenant
+    pub fn label(&self) -> String {
+        if self.tenant.is_empty() {
+            self.target.to_string()
+        } else {
+            format!("{} ({})", self.target, self.tenant)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct QueuedJob {
+    id: String,
+    request: ScanRequest,
+    priority: JobPriority,
+    sequence: u64, // Submission order, kept across restarts
+    #[serde(default)]
+    owners: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct JobQueue {
+    config: SharedConfig,
+    jobs: JobRegistry,
+    tree: sled::Tree,
+    wakeup: Arc<Notify>,
+    lock: Arc<Mutex<()>>, // De-duplication and selection of the next job see the same queue
+}
+
+impl JobQueue {
+    // Jobs left by the previous run are queued again, the running ones from the start
+    pub fn open(config: SharedConfig) -> Result<Self, MyError> {
+        let db = config
+            .current()
+            .db
+            .clone()
+            .ok_or_else(|| CustomError::database_error("Database is not initialized"))?;
+        let tree = db
+            .open_tree(QUEUE_TREE)
+            .map_err(|e| CustomError::Database { context: format!("Failed to open the {} tree", QUEUE_TREE), source: e })?;
+
+        let queue = JobQueue { config, jobs: JobRegistry::default(), tree, wakeup: Arc::new(Notify::new()), lock: Arc::default() };
+        let restored = queue.queued_jobs()?;
+        for queued in &restored {
+            queue.jobs.create(&queued.id, &queued.request.label(), &queued.request.tenant, &queued.owners);
+        }
+        if !restored.is_empty() {
+            tracing::info!("{} jobs of the previous run queued again", restored.len());
+        }
+        Ok(queue)
+    }
+
+    // Worker and scheduler, for the lifetime of the server
+    pub fn start(&self) {
+        tokio::spawn(run_worker(self.clone()));
+        tokio::spawn(run_schedules(self.clone()));
+    }
+
+    // Snapshot taken when a job starts, a reload only affects the next ones
+    pub fn config(&self) -> Arc<AppConfig> {
+        self.config.current()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.jobs.get(id)
+    }
+
+    // Job of an identical request not finished yet, raised to the new priority, or a new job.
+    // `owner` is the caller of the request, empty for the schedules and messages: a caller only joins its own jobs
+    // and the ones without owner, so it never gets the job id of another caller
+    pub fn enqueue(&self, request: ScanRequest, priority: JobPriority, owner: &str) -> Result<Arc<Job>, MyError> {
+        // Project keys are compared like the `projects` of a source
+        if let ScanTarget::Project { name } = &request.target {
+            if !request.projects.is_empty() && !request.projects.iter().any(|project| project.eq_ignore_ascii_case(name)) {
+                return Err(CustomError::auth(format!("Not allowed to scan project '{}'", name)));
+            }
+        }
+        // Unknown tenants and plugins are refused now
+        let config = self.config();
+        config_for_request(config_for_tenant(&config, &request.tenant)?, &request.branch, &request.plugins, &request.projects)?;
+        let _guard = self.lock.lock().unwrap();
+
+        for mut queued in self.queued_jobs()? {
+            let job = match self.jobs.get(&queued.id) {
+                Some(job) if !job.state().is_finished() => job,
+                _ => continue,
+            };
+            let shared = owner.is_empty() || queued.owners.is_empty() || queued.owners.iter().any(|known| known == owner);
+            if !shared || !same_request(&queued.request, &request) {
+                continue;
+            }
+            let joined = !owner.is_empty() && job.add_owner(owner);
+            let raised = priority < queued.priority;
+            if joined || raised {
+                queued.owners = job.owners();
+                queued.priority = queued.priority.min(priority);
+                self.save(&queued)?;
+            }
+            tracing::info!("Job {} already covers {}", job.id, job.target);
+            return Ok(job);
+        }
+
+        let sequence = self
+            .config()
+            .db
+            .as_ref()
+            .ok_or_else(|| CustomError::database_error("Database is not initialized"))?
+            .generate_id()
+            .map_err(|e| CustomError::Database { context: "Failed to number the job".to_string(), source: e })?;
+        let owners = if owner.is_empty() { vec![] } else { vec![owner.to_string()] };
+        let queued = QueuedJob { id: uuid::Uuid::new_v4().to_string(), request, priority, sequence, owners };
+        self.save(&queued)?;
+        let job = self.jobs.create(&queued.id, &queued.request.label(), &queued.request.tenant, &queued.owners);
+        tracing::info!("Job {} queued ({:?}): {}", job.id, priority, job.target);
+        self.wakeup.notify_one();
+        Ok(job)
+    }
+
+    // Highest priority then oldest job, marked running. Jobs cancelled while queued are dropped
+    pub fn next(&self) -> Result<Option<(Arc<Job>, ScanRequest)>, MyError> {
+        let _guard = self.lock.lock().unwrap();
+        let mut queued_jobs = self.queued_jobs()?;
+        queued_jobs.sort_by_key(|queued| (queued.priority, queued.sequence));
+
+        for queued in queued_jobs {
+            match self.jobs.get(&queued.id) {
+                Some(job) if job.start() => return Ok(Some((job, queued.request))),
+                Some(job) if !job.state().is_finished() => continue, // Running
+                _ => self.remove(&queued.id)?,
+            }
+        }
+        Ok(None)
+    }
+
+    pub async fn wait_for_jobs(&self) {
+        self.wakeup.notified().await;
+    }
+
+    // A finished job is not queued again after a restart
+    pub fn remove(&self, id: &str) -> Result<(), MyError> {
+        self.tree
+            .remove(id)
+            .map_err(|e| CustomError::Database { context: format!("Failed to remove job {}", id), source: e })?;
+        Ok(())
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<Option<Arc<Job>>, MyError> {
+        let Some(job) = self.jobs.get(id) else {
+            return Ok(None);
+        };
+        if job.cancel() {
+            tracing::info!("Job {} cancelled", job.id);
+            self.remove(id)?;
+        }
+        Ok(Some(job))
+    }
+
+    fn queued_jobs(&self) -> Result<Vec<QueuedJob>, MyError> {
+        self.tree
+            .iter()
+            .values()
+            .map(|value| {
+                let value = value.map_err(|e| CustomError::Database { context: "Failed to read the job queue".to_string(), source: e })?;
+                serde_json::from_slice(&value).map_err(|e| CustomError::parse("queued job", e))
+            })
+            .collect()
+    }
+
+    fn save(&self, queued: &QueuedJob) -> Result<(), MyError> {
+        let value = serde_json::to_vec(queued).map_err(|e| CustomError::parse("queued job", e))?;
+        self.tree
+            .insert(queued.id.as_bytes(), value)
+            .map_err(|e| CustomError::Database { context: format!("Failed to queue job {}", queued.id), source: e })?;
+        Ok(())
+    }
+}
+
+// Plugins and projects are selections, their order does not matter
+fn same_request(a: &ScanRequest, b: &ScanRequest) -> bool {
+    let sorted = |plugins: &[String]| {
+        let mut plugins = plugins.to_vec();
+        plugins.sort();
+        plugins.dedup();
+        plugins
+    };
+    a.target == b.target
+        && a.tenant == b.tenant
+        && a.branch == b.branch
+        && sorted(&a.plugins) == sorted(&b.plugins)
+        && sorted(&a.projects) == sorted(&b.projects)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(plugins: &[&str], projects: &[&str]) -> ScanRequest {
+        ScanRequest {
+            target: ScanTarget::Repo { name: "my-repo".to_string() },
+            branch: String::new(),
+            plugins: plugins.iter().map(|plugin| plugin.to_string()).collect(),
+            projects: projects.iter().map(|project| project.to_string()).collect(),
+            tenant: String::new(),
+        }
+    }
+
+    fn queue() -> JobQueue {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        JobQueue::open(SharedConfig::new(AppConfig { db: Some(db), ..AppConfig::default() })).unwrap()
+    }
+
+    fn project_request(name: &str, projects: &[&str]) -> ScanRequest {
+        ScanRequest { target: ScanTarget::Project { name: name.to_string() }, ..request(&[], projects) }
+    }
+
+    #[test]
+    fn enqueue_refuses_the_projects_the_caller_may_not_scan() {
+        let queue = queue();
+        assert!(queue.enqueue(project_request("ptep", &["PTEP"]), JobPriority::OnDemand, "ci").is_ok());
+        assert!(queue.enqueue(project_request("OTHER", &[]), JobPriority::OnDemand, "ci").is_ok());
+
+        let e = queue.enqueue(project_request("OTHER", &["PTEP"]), JobPriority::OnDemand, "ci").unwrap_err();
+        assert_eq!(CustomError::kind(e.as_ref()), "auth");
+        assert!(e.to_string().contains("Not allowed to scan project 'OTHER'"), "{}", e);
+    }
+
+    #[test]
+    fn enqueue_shares_a_job_with_its_owners_only() {
+        let queue = queue();
+        let alice = queue.enqueue(request(&[], &[]), JobPriority::OnDemand, "alice").unwrap();
+        assert_eq!(queue.enqueue(request(&[], &[]), JobPriority::OnDemand, "alice").unwrap().id, alice.id);
+        assert_ne!(queue.enqueue(request(&[], &[]), JobPriority::OnDemand, "bob").unwrap().id, alice.id);
+
+        // A caller joins a scheduled job and owns it from then on
+        let scheduled = queue.enqueue(project_request("PTEP", &[]), JobPriority::Scheduled, "").unwrap();
+        assert!(scheduled.owners().is_empty());
+        assert_eq!(queue.enqueue(project_request("PTEP", &[]), JobPriority::OnDemand, "alice").unwrap().id, scheduled.id);
+        assert_eq!(scheduled.owners(), vec!["alice"]);
+    }
+
+    #[test]
+    fn same_request_ignores_the_order_of_the_selections() {
+        assert!(same_request(&request(&["maven", "npm"], &["A", "B"]), &request(&["npm", "maven", "npm"], &["B", "A"])));
+    }
+
+    #[test]
+    fn same_request_compares_target_branch_tenant_and_selections() {
+        let base = request(&["maven"], &[]);
+        assert!(!same_request(&base, &request(&["npm"], &[])));
+        assert!(!same_request(&base, &request(&["maven"], &["A"])));
+        assert!(!same_request(&base, &ScanRequest { branch: "develop".to_string(), ..base.clone() }));
+        assert!(!same_request(&base, &ScanRequest { tenant: "mayorana".to_string(), ..base.clone() }));
+        assert!(!same_request(&base, &ScanRequest { target: ScanTarget::Repo { name: "other".to_string() }, ..base.clone() }));
+        assert!(!same_request(&base, &ScanRequest { target: ScanTarget::All, ..base.clone() }));
+    }
+}
@@ -0,0 +1,259 @@
+pub mod emit_event;
+pub mod job_queue;
+pub mod run_job;
+pub mod run_worker;
+pub mod run_schedules;
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use serde_json::Value;
+use tokio::sync::broadcast;
+use tokio::task::AbortHandle;
+
+use crate::models::{AnalysisStatus, KPIStatus};
+use crate::types::{error_chain, MyError};
+
+// Reports of a scan by project, as written to the output folder
+pub type ScanResults = HashMap<String, Vec<Value>>;
+
+// Results of a finished scan, shared by every request waiting for the job
+pub type JobOutcome = Result<Arc<ScanResults>, Arc<MyError>>;
+
+// Live events kept for a slow watcher before it starts missing some (the history still has them)
+const EVENT_CAPACITY: usize = 1024;
+
+// Finished jobs are forgotten after this delay, and the oldest ones beyond this count
+const FINISHED_JOB_TTL: Duration = Duration::from_secs(24 * 3600);
+const MAX_FINISHED_JOBS: usize = 1000;
+
+// Progress of a scan, sent to the clients watching its job
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ScanEvent {
+    JobAccepted { target: String },
+    RepositoryStarted { project: String, repository: String },
+    PluginFinished { repository: String, plugin: String, findings: usize, error: Option<String> },
+    FindingProduced { repository: String, plugin: String, product: String, cycle: String, file_path: Option<String> },
+    KpiComputed { repository: String, product: String, cycle: String, status: KPIStatus },
+    RepositoryFinished { repository: String, status: AnalysisStatus },
+    JobFinished { state: JobState, message: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobState {
+    pub fn is_finished(self) -> bool {
+        !matches!(self, JobState::Queued | JobState::Running)
+    }
+}
+
+#[derive(Debug)]
+struct JobProgress {
+    state: JobState,
+    events: Vec<ScanEvent>, // Replayed to the clients reconnecting to the job
+    abort: Option<AbortHandle>,
+    outcome: Option<JobOutcome>, // Not set when cancelled
+    finished_at: Option<Instant>,
+}
+
+// A scan waiting in the queue or running in the background, identified by its id
+#[derive(Debug)]
+pub struct Job {
+    pub id: String,
+    pub target: String,
+    pub tenant: String,          // Top level configuration when empty
+    owners: Mutex<Vec<String>>, // Callers that queued or joined the job, none for the schedules and messages
+    progress: Mutex<JobProgress>,
+    sender: broadcast::Sender<(usize, ScanEvent)>, // With the position of the event in the history
+}
+
+impl Job {
+    // History and live events are updated under the same lock, a watcher gets each event once
+    pub fn emit(&self, event: ScanEvent) {
+        let mut progress = self.progress.lock().unwrap();
+        if progress.state.is_finished() {
+            return;
+        }
+        if let ScanEvent::JobFinished { state, .. } = &event {
+            progress.state = *state;
+            progress.abort = None;
+            progress.finished_at = Some(Instant::now());
+        }
+        let sequence = progress.events.len();
+        progress.events.push(event.clone());
+        let _ = self.sender.send((sequence, event)); // No receiver is not an error
+    }
+
+    // Events so far, and the receiver of the next ones
+    pub fn subscribe(&self) -> (Vec<ScanEvent>, broadcast::Receiver<(usize, ScanEvent)>) {
+        let progress = self.progress.lock().unwrap();
+        (progress.events.clone(), self.sender.subscribe())
+    }
+
+    pub fn owners(&self) -> Vec<String> {
+        self.owners.lock().unwrap().clone()
+    }
+
+    // A caller joining the job of an identical request, false when it already owns it
+    pub fn add_owner(&self, owner: &str) -> bool {
+        let mut owners = self.owners.lock().unwrap();
+        if owners.iter().any(|known| known == owner) {
+            return false;
+        }
+        owners.push(owner.to_string());
+        true
+    }
+
+    pub fn state(&self) -> JobState {
+        self.progress.lock().unwrap().state
+    }
+
+    pub fn event_count(&self) -> usize {
+        self.progress.lock().unwrap().events.len()
+    }
+
+    pub fn outcome(&self) -> Option<JobOutcome> {
+        self.progress.lock().unwrap().outcome.clone()
+    }
+
+    // Taken by a worker, false when the job was cancelled while queued
+    pub fn start(&self) -> bool {
+        let mut progress = self.progress.lock().unwrap();
+        if progress.state != JobState::Queued {
+            return false;
+        }
+        progress.state = JobState::Running;
+        true
+    }
+
+    // A scan cancelled before its task was spawned is aborted right away
+    pub fn set_abort_handle(&self, abort: AbortHandle) {
+        let mut progress = self.progress.lock().unwrap();
+        match progress.state {
+            JobState::Running => progress.abort = Some(abort),
+            JobState::Cancelled => abort.abort(),
+            _ => {}
+        }
+    }
+
+    // Keeps the results for the waiting requests, then ends the event stream
+    pub fn finish(&self, outcome: Result<ScanResults, MyError>) {
+        let (state, message, outcome) = match outcome {
+            Ok(results) => {
+                let repositories: usize = results.values().map(Vec::len).sum();
+                (JobState::Completed, format!("{} repositories reported", repositories), Ok(Arc::new(results)))
+            }
+            Err(e) => (JobState::Failed, error_chain(e.as_ref()), Err(Arc::new(e))),
+        };
+        {
+            let mut progress = self.progress.lock().unwrap();
+            if progress.state.is_finished() {
+                return;
+            }
+            progress.outcome = Some(outcome);
+        }
+        tracing::info!("Job {} {:?}: {}", self.id, state, message);
+        self.emit(ScanEvent::JobFinished { state, message });
+    }
+
+    // Removed from the queue, or stopped at its next await point. False when the job is already over
+    pub fn cancel(&self) -> bool {
+        let abort = {
+            let mut progress = self.progress.lock().unwrap();
+            if progress.state.is_finished() {
+                return false;
+            }
+            progress.abort.take()
+        };
+        if let Some(abort) = abort {
+            abort.abort();
+        }
+        self.emit(ScanEvent::JobFinished { state: JobState::Cancelled, message: "Cancelled".to_string() });
+        true
+    }
+
+    // Results once the job is over, none when it was cancelled
+    pub async fn results(&self) -> Option<JobOutcome> {
+        self.wait().await;
+        self.outcome()
+    }
+
+    // State once the job is over
+    pub async fn wait(&self) -> JobState {
+        let (history, mut receiver) = self.subscribe();
+        if history.iter().any(|event| matches!(event, ScanEvent::JobFinished { .. })) {
+            return self.state();
+        }
+        loop {
+            match receiver.recv().await {
+                Ok((_, ScanEvent::JobFinished { state, .. })) => return state,
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => return self.state(),
+            }
+        }
+    }
+}
+
+// Jobs of the running server, finished ones are kept in memory for a while
+#[derive(Debug, Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, Arc<Job>>>>,
+}
+
+impl JobRegistry {
+    // Queued, the id is kept when the job is restored after a restart
+    pub fn create(&self, id: &str, target: &str, tenant: &str, owners: &[String]) -> Arc<Job> {
+        let (sender, _) = broadcast::channel(EVENT_CAPACITY);
+        let job = Arc::new(Job {
+            id: id.to_string(),
+            target: target.to_string(),
+            tenant: tenant.to_string(),
+            owners: Mutex::new(owners.to_vec()),
+            progress: Mutex::new(JobProgress {
+                state: JobState::Queued,
+                events: vec![],
+                abort: None,
+                outcome: None,
+                finished_at: None,
+            }),
+            sender,
+        });
+        job.emit(ScanEvent::JobAccepted { target: target.to_string() });
+        let mut jobs = self.jobs.lock().unwrap();
+        evict_finished_jobs(&mut jobs);
+        jobs.insert(job.id.clone(), Arc::clone(&job));
+        job
+    }
+
+    pub fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.jobs.lock().unwrap().get(id).cloned()
+    }
+}
+
+// Finished jobs past their TTL, then the oldest finished ones beyond the cap. The requests still waiting keep their job
+fn evict_finished_jobs(jobs: &mut HashMap<String, Arc<Job>>) {
+    let mut finished: Vec<(Instant, String)> = jobs
+        .values()
+        .filter_map(|job| job.progress.lock().unwrap().finished_at.map(|finished_at| (finished_at, job.id.clone())))
+        .collect();
+    finished.sort();
+
+    let expired = finished.iter().take_while(|(finished_at, _)| finished_at.elapsed() > FINISHED_JOB_TTL).count();
+    let evicted = expired.max(finished.len().saturating_sub(MAX_FINISHED_JOBS));
+    for (_, id) in &finished[..evicted] {
+        jobs.remove(id);
+    }
+    if evicted > 0 {
+        tracing::debug!("{} finished jobs forgotten", evicted);
+    }
+}
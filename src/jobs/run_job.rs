@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use crate::boot::config_for_request::config_for_request;
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::cli::run_scan::run_scan;
+use crate::jobs::Job;
+use crate::jobs::job_queue::ScanRequest;
+use crate::models::AppConfig;
+use crate::types::CustomError;
+
+/// Run the scan of a job taken from the queue, its progress is sent to the clients watching the job.
+/// The scan goes on when they disconnect, until it ends or the job is cancelled.
+pub async fn run_job(config: &AppConfig, job: Arc<Job>, request: ScanRequest) {
+    // The tenant may have been removed by a reload since the job was queued
+    let config = match config_for_tenant(config, &request.tenant)
+        .and_then(|config| config_for_request(config, &request.branch, &request.plugins, &request.projects))
+    {
+        Ok(config) => AppConfig { job: Some(Arc::clone(&job)), ..config },
+        Err(e) => return job.finish(Err(e)),
+    };
+
+    let target = request.target;
+    let task = tokio::spawn(async move { run_scan(&config, &target).await });
+    job.set_abort_handle(task.abort_handle());
+
+    match task.await {
+        Ok(outcome) => job.finish(outcome),
+        Err(e) if e.is_cancelled() => {} // The cancellation already ended the job
+        Err(e) => job.finish(Err(CustomError::project_error(format!("Scan task failed: {}", e)))),
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+use chrono::{Local, Timelike};
+use croner::Cron;
+
+use crate::cli::ScanTarget;
+use crate::jobs::job_queue::{JobPriority, JobQueue, ScanRequest};
+
+// Checked at the start of every minute against the current configuration, reloads included.
+// A schedule missed while the server was down is not caught up.
+pub async fn run_schedules(queue: JobQueue) {
+    loop {
+        let now = Local::now();
+        let elapsed = Duration::from_secs(now.second() as u64) + Duration::from_nanos(now.nanosecond() as u64);
+        tokio::time::sleep(Duration::from_secs(60).saturating_sub(elapsed)).await;
+
+        let now = Local::now();
+        let minute = now.with_second(0).and_then(|now| now.with_nanosecond(0)).unwrap_or(now);
+        for schedule in &queue.config().schedules {
+            let due = Cron::new(&schedule.cron)
+                .parse()
+                .and_then(|cron| cron.is_time_matching(&minute));
+            match due {
+                Ok(true) => {
+                    let request = ScanRequest {
+                        target: ScanTarget::from(&schedule.job),
+                        branch: String::new(),
+                        plugins: vec![],
+                        projects: vec![],
+                        tenant: schedule.job.tenant.clone().unwrap_or_default(),
+                    };
+                    // A scan still queued or running from the previous occurrence is not queued twice
+                    if let Err(e) = queue.enqueue(request, JobPriority::Scheduled, "") {
+                        tracing::error!("Schedule '{}' not queued: {}", schedule.cron, e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => tracing::warn!("Invalid schedule '{}': {}", schedule.cron, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use crate::jobs::job_queue::JobQueue;
+use crate::jobs::run_job::run_job;
+
+// One job at a time, the repositories of a job are analysed `concurrency` at a time
+pub async fn run_worker(queue: JobQueue) {
+    loop {
+        match queue.next() {
+            Ok(Some((job, request))) => {
+                tracing::info!("Job {} started: {}", job.id, job.target);
+                run_job(&queue.config(), job.clone(), request).await;
+                if let Err(e) = queue.remove(&job.id) {
+                    tracing::error!("Job {} stays in the queue: {}", job.id, e);
+                }
+            }
+            Ok(None) => queue.wait_for_jobs().await,
+            Err(e) => {
+                tracing::error!("Cannot read the job queue: {}", e);
+                queue.wait_for_jobs().await;
+            }
+        }
+    }
+}
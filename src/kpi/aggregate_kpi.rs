@@ -0,0 +1,105 @@
+
+use std::collections::BTreeMap;
+use tracing::debug;
+
+use crate::models::{KPIResult, ProductVerdict};
+use crate::kpi::utils::compare_versions::compare_versions;
+
+/// Aggregate the KPI of every occurrence of a product into a single verdict (worst, best, spread)
+pub fn aggregate_kpi(kpi_results: &[KPIResult]) -> Vec<ProductVerdict> {
+    let mut by_product: BTreeMap<&str, Vec<&KPIResult>> = BTreeMap::new();
+    for kpi in kpi_results {
+        by_product.entry(kpi.product.as_str()).or_default().push(kpi);
+    }
+
+    by_product
+        .into_iter()
+        .filter_map(|(product, occurrences)| {
+            // On equal status, the lowest version is the worst one
+            let worst = occurrences.iter().max_by(|a, b| {
+                a.status.severity().cmp(&b.status.severity())
+                    .then_with(|| compare_versions(&b.cycle, &a.cycle))
+            })?;
+            let best = occurrences.iter().min_by(|a, b| {
+                a.status.severity().cmp(&b.status.severity())
+                    .then_with(|| compare_versions(&b.cycle, &a.cycle))
+            })?;
+
+            let mut cycles: Vec<String> = occurrences.iter().map(|kpi| kpi.cycle.clone()).collect();
+            cycles.sort_by(|a, b| compare_versions(a, b));
+            cycles.dedup();
+
+            if cycles.len() > 1 {
+                debug!("Version skew for product {}: {:?}", product, cycles);
+            }
+
+            Some(ProductVerdict {
+                product: product.to_string(),
+                worst: worst.status,
+                worst_cycle: worst.cycle.clone(),
+                best: best.status,
+                best_cycle: best.cycle.clone(),
+                cycles,
+                occurrences: occurrences.len(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Evidence, KPIStatus};
+
+    fn kpi(product: &str, cycle: &str, status: KPIStatus) -> KPIResult {
+        KPIResult {
+            product: product.to_string(),
+            cycle: cycle.to_string(),
+            status,
+            reason: String::new(),
+            source: None,
+            validity: None,
+            evidence: Evidence::default(),
+        }
+    }
+
+    #[test]
+    fn worst_and_best_occurrence_of_each_product() {
+        let verdicts = aggregate_kpi(&[
+            kpi("java", "17", KPIStatus::UpToDate),
+            kpi("java", "8", KPIStatus::Outdated),
+            kpi("java", "11", KPIStatus::Compliant),
+            kpi("angular", "16", KPIStatus::NonCompliant),
+        ]);
+        assert_eq!(verdicts.len(), 2);
+
+        let angular = &verdicts[0];
+        assert_eq!(angular.product, "angular");
+        assert_eq!((angular.worst, angular.best), (KPIStatus::NonCompliant, KPIStatus::NonCompliant));
+        assert_eq!((angular.occurrences, angular.cycles.len()), (1, 1));
+
+        let java = &verdicts[1];
+        assert_eq!((java.worst, java.worst_cycle.as_str()), (KPIStatus::Outdated, "8"));
+        assert_eq!((java.best, java.best_cycle.as_str()), (KPIStatus::UpToDate, "17"));
+        assert_eq!(java.cycles, vec!["8", "11", "17"]);
+        assert_eq!(java.occurrences, 3);
+    }
+
+    #[test]
+    fn lowest_version_is_the_worst_on_equal_status() {
+        let verdicts = aggregate_kpi(&[
+            kpi("node", "18", KPIStatus::Outdated),
+            kpi("node", "16", KPIStatus::Outdated),
+            kpi("node", "18", KPIStatus::Outdated),
+        ]);
+        assert_eq!(verdicts[0].worst_cycle, "16");
+        assert_eq!(verdicts[0].best_cycle, "18");
+        assert_eq!(verdicts[0].cycles, vec!["16", "18"]);
+        assert_eq!(verdicts[0].occurrences, 3);
+    }
+
+    #[test]
+    fn no_verdict_without_kpi() {
+        assert!(aggregate_kpi(&[]).is_empty());
+    }
+}
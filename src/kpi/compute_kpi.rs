@@ -8,13 +8,13 @@ use crate::kpi::utils::version_matches::version_matches;
 use crate::kpi::utils::is_valid_timeframe::is_valid_timeframe;
 use crate::kpi::find_upgrade_suggestions::find_upgrade_suggestions;
 
-pub fn compute_kpi<'a>(config: &AppConfig, analysis: &'a mut Analysis) -> Option<KPIResult> {
+pub fn compute_kpi(config: &AppConfig, analysis: &mut Analysis) -> Option<KPIResult> {
     let cycle = sanitize_version(&analysis.dependency_version.cycle);
     let today = Utc::now().date_naive();
     debug!("Analyzing KPI for {:?}", analysis.dependency_version);
 
     let (oldest_suggestion, latest_suggestion) = analysis.roadmap.as_mut()
-        .map(|roadmap| find_upgrade_suggestions(&config, &mut roadmap.entries))
+        .map(|roadmap| find_upgrade_suggestions(config, &mut roadmap.entries))
         .unwrap_or((None, None));
 
     analysis.roadmap.as_ref().and_then(|roadmap| {
@@ -37,6 +37,7 @@ pub fn compute_kpi<'a>(config: &AppConfig, analysis: &'a mut Analysis) -> Option
                     reason,
                     source: Some(source_name.clone()),
                     validity: Some(days.to_string()),
+                    evidence: analysis.evidence.clone(),
                 }
             })
         },
@@ -102,6 +103,7 @@ pub fn compute_kpi<'a>(config: &AppConfig, analysis: &'a mut Analysis) -> Option
                 reason,
                 source: latest_suggestion.as_ref().map(|(_, source_name)| source_name.clone()), // Set the source if available
                 validity: Some(days.to_string()), // Always set `days` for valid and outdated cases
+                evidence: analysis.evidence.clone(),
             })
         }
     )
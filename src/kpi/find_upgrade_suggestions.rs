@@ -7,10 +7,13 @@ use crate::kpi::is_lower_version::is_lower_version;
 use crate::kpi::utils::compare_versions::compare_versions;
 use crate::models::AppConfig;
 
+// (cycle, source_name) of a suggested upgrade target
+pub type Suggestion = Option<(String, String)>;
+
 pub fn find_upgrade_suggestions(
     config: &AppConfig,
-    entries: &mut Vec<RoadmapEntry>,
-) -> (Option<(String, String)>, Option<(String, String)>) {
+    entries: &mut [RoadmapEntry],
+) -> (Suggestion, Suggestion) {
     let today = Utc::now().date_naive();
     debug!("Starting to find upgrade suggestions for today's date: {}", today);
 
@@ -3,3 +3,4 @@ pub mod utils;
 pub mod find_upgrade_suggestions;
 pub mod is_lower_version;
 
+pub mod aggregate_kpi;
@@ -1,5 +1,8 @@
 
+mod auth;
 mod boot;
+mod cli;
+mod credentials;
 mod display_menu;
 mod kpi;
 mod models;
@@ -10,18 +13,30 @@ mod url;
 mod utils;
 mod fetch_repositories;
 mod grpc_server;
+mod rest_server;
+mod jobs;
 mod types;
-mod consume_messages;
+mod messaging;
 // mod error;
 
-use std::env;
-use std::sync::Arc;
+use std::io::IsTerminal;
 
+use clap::Parser;
+
+use crate::boot::check_base_urls::check_base_urls;
+use crate::boot::config_for_tenant::config_for_tenant;
 use crate::boot::load_config::load_config;
-use crate::display_menu::display_menu;
+use crate::boot::open_tenant_databases::open_tenant_databases;
+use crate::boot::validate_config::{has_errors, print_diagnostics, validate_config};
+use crate::cli::{Cli, Command, ScanTarget, EXIT_POLICY_BREACHED};
+use crate::services::load_results::load_results;
+use crate::cli::print_policy_report::print_policy_report;
+use crate::cli::run_jobs::run_jobs;
+use crate::cli::run_roadmap::run_roadmap;
+use crate::cli::run_scan::run_scan;
+use crate::cli::serve::{serve, ServeMode};
 use crate::roadmap::process_yaml_files::process_yaml_files;
-use crate::services::analyze_specific_repository::analyze_specific_repository;
-use crate::grpc_server::start_grpc_server;
+use crate::services::evaluate_policy::evaluate_policy;
 use types::{CustomError, MyError};
 use tokio::task::spawn_blocking;
 use tracing::{info, error};
@@ -29,13 +44,40 @@ use tracing::{info, error};
 
 #[tokio::main]
 async fn main() -> Result<(), MyError> {
+    let cli = Cli::parse();
+    let config_file_path = cli.config.clone();
+
+    // `xdebt validate`: every check, base URLs included, then exit
+    if let Some(Command::Validate) = cli.command {
+        let (config_file, mut diagnostics) = validate_config(&config_file_path);
+        if let Some(config_file) = &config_file {
+            diagnostics.extend(check_base_urls(&config_file_path, config_file).await);
+        }
+        print_diagnostics(&diagnostics);
+        if has_errors(&diagnostics) {
+            std::process::exit(1);
+        }
+        println!("{} is valid", config_file_path);
+        return Ok(());
+    }
+
+    // Refuse to start on an invalid configuration, warnings are only printed
+    let (_, diagnostics) = validate_config(&config_file_path);
+    print_diagnostics(&diagnostics);
+    if has_errors(&diagnostics) {
+        return Err(CustomError::config(format!(
+            "{} is invalid, see the errors above (`xdebt validate` runs every check)",
+            config_file_path
+        )));
+    }
+
     // Load configuration
-    let config_file_path = "configuration.yml";
     info!("Loading configuration from {}", config_file_path);
-
-    let config_result = spawn_blocking(move || {
-        load_config(config_file_path).unwrap()
-    }).await?;
+    let overrides = cli.overrides();
+    let config_result = {
+        let (path, overrides) = (config_file_path.clone(), overrides.clone());
+        spawn_blocking(move || load_config(&path, &overrides)).await??
+    };
 
     // Initialize the database
     info!("Initializing the Sled database...");
@@ -43,7 +85,8 @@ async fn main() -> Result<(), MyError> {
         Ok(Ok(db)) => db,
         Ok(Err(e)) => {
             error!("Failed to open the database: {}", e);
-            return Err(CustomError::database_error("Failed to initialize the database"));
+            let e: MyError = Box::new(CustomError::Database { context: "Failed to open roadmap_db".to_string(), source: e });
+            return Err(e);
         }
         Err(e) => {
             error!("Failed to spawn database initialization task: {}", e);
@@ -55,47 +98,54 @@ async fn main() -> Result<(), MyError> {
     let item_count = db.iter().count();
     info!("Number of items in the database: {}", item_count);
 
-    // Incorporate the DB into the config
+    // Incorporate the DB into the config, each tenant has its own
     let mut config = config_result;
     config.db = Some(db);
-    let shared_config = Arc::new(config);  // Arc-wrapped, no Mutex needed
+    open_tenant_databases(&mut config)?;
 
     // Process YAML files
     info!("Processing YAML files in the roadmap folder...");
-    process_yaml_files(&shared_config, &shared_config.roadmap_folder).await?;
+    process_yaml_files(&config, &config.roadmap_folder).await?;
+    for (name, tenant) in &config.tenants {
+        info!("Processing the roadmap of tenant '{}'", name);
+        process_yaml_files(tenant, &tenant.roadmap_folder).await?;
+    }
     info!("YAML files processed successfully.");
 
-    // Start the gRPC server
-    let grpc_config = Arc::clone(&shared_config);
-    let grpc_handle = tokio::spawn(async move {
-        if let Err(e) = start_grpc_server(grpc_config).await {
-            eprintln!("gRPC server failed: {}", e);
-        }
-    });
-
-    // Handle command-line argument for specific repository analysis
-    let args: Vec<String> = env::args().collect();
-    if args.len() > 1 {
-        let repo_name = &args[1];
-        analyze_specific_repository(&shared_config, Some(repo_name)).await?;
-        return Ok(());
+    // The server selects the tenant of each request, the other commands use the one of `--tenant`
+    if let Some(tenant) = cli.tenant.as_deref().filter(|_| !matches!(cli.command, Some(Command::Serve))) {
+        config = config_for_tenant(&config, tenant)?.clone();
     }
 
-    // Run display menu in a loop
-    let menu_handle = tokio::spawn(async move {
-        loop {
-            let _ = display_menu(&shared_config).await;
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+    let results = match cli.command {
+        // The menu needs someone to answer it, Docker and CI run the configured jobs
+        None if std::io::stdin().is_terminal() => return serve(config, &config_file_path, overrides, ServeMode::Menu).await,
+        None | Some(Command::Run) => {
+            info!("Running the {} configured jobs", config.jobs.len());
+            run_jobs(&config).await?
+        }
+        Some(Command::Serve) => return serve(config, &config_file_path, overrides, ServeMode::Grpc).await,
+        Some(Command::Validate) => return Ok(()),
+        Some(Command::Roadmap { command }) => return run_roadmap(&config, &command, cli.format).await,
+        Some(Command::Scan { target }) => run_scan(&config, &target).await?,
+        Some(Command::Report { project }) => load_results(&config, project.as_deref())?,
+        // Former `xdebt <repository>` invocation
+        Some(Command::Repository(args)) => {
+            let name = args.into_iter().next().unwrap_or_default();
+            run_scan(&config, &ScanTarget::Repo { name }).await?
         }
-    });
-
-    // Wait for gRPC and menu tasks to complete
-    tokio::select! {
-        _ = grpc_handle => println!("gRPC server task finished."),
-        _ = menu_handle => println!("Menu task finished."),
     };
 
+    // Scan, run and report exit codes follow the policy thresholds
+    let report = evaluate_policy(&config, &results);
+    print_policy_report(&report, cli.format)?;
+    if !report.breaches.is_empty() {
+        // `exit` skips the drop of the database, the last scan states and cache entries are written first
+        if let Some(db) = &config.db {
+            db.flush_async().await?;
+        }
+        std::process::exit(EXIT_POLICY_BREACHED);
+    }
+
     Ok(())
 }
-
-
@@ -0,0 +1,34 @@
+use std::env;
+use anyhow::{Context, Result};
+use iggy::client::{Client, UserClient};
+use iggy::clients::client::IggyClient;
+
+use crate::models::IggyConfig;
+
+// Default password of a new Iggy server, for local tests
+const DEFAULT_PASSWORD: &str = "iggy";
+
+// TCP client logged in as `username`, it reconnects on its own after a connection loss
+pub async fn connect_iggy(config: &IggyConfig) -> Result<IggyClient> {
+    dotenv::dotenv().ok();
+    let password = match &config.password_env {
+        Some(var) => env::var(var).with_context(|| format!("Missing Iggy password, set {}", var))?,
+        None => DEFAULT_PASSWORD.to_string(),
+    };
+
+    let client = IggyClient::builder()
+        .with_tcp()
+        .with_server_address(config.address.clone())
+        .build()
+        .context("Failed to create the Iggy client")?;
+    client
+        .connect()
+        .await
+        .with_context(|| format!("Failed to connect to Iggy at {}", config.address))?;
+    client
+        .login_user(&config.username, &password)
+        .await
+        .with_context(|| format!("Failed to log in to Iggy as {}", config.username))?;
+
+    Ok(client)
+}
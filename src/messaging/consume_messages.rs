@@ -0,0 +1,141 @@
+use std::time::Duration;
+use anyhow::{anyhow, Context, Result};
+use futures::StreamExt;
+use iggy::clients::client::IggyClient;
+use iggy::clients::consumer::{AutoCommit, ReceivedMessage};
+use iggy::clients::producer::IggyProducer;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::jobs::job_queue::JobQueue;
+use crate::messaging::ensure_topic::ensure_topic;
+use crate::messaging::process_with_retries::process_with_retries;
+use crate::messaging::processed_messages::{load_processed_message, prune_processed_messages, save_processed_message, ProcessedMessage};
+use crate::messaging::publish_message::publish_message;
+use crate::messaging::{request_id, DeadLetter, MessageStatus};
+use crate::models::IggyConfig;
+
+// Records older than `iggy.retention_days` are forgotten at the start of the consumer, then this often
+const PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Requests of one stream, processed one after the other. `tenant` is empty for the stream of the top level configuration.
+/// The offset of a request is committed once its result is saved and published, a request read again is not processed twice.
+pub async fn consume_messages(queue: &JobQueue, client: &IggyClient, stream: &str, tenant: &str) -> Result<()> {
+    let config = queue.config();
+    let iggy = config.iggy.clone();
+    let db = config_for_tenant(&config, tenant)
+        .map_err(|e| anyhow!(e))?
+        .db
+        .clone()
+        .context("Database is not initialized")?;
+    ensure_topic(client, stream, &iggy.topic).await?;
+
+    let mut consumer = client
+        .consumer_group(&iggy.consumer_group, stream, &iggy.topic)
+        .context("Failed to create consumer group")?
+        .create_consumer_group_if_not_exists()
+        .auto_join_consumer_group()
+        .auto_commit(AutoCommit::Disabled)
+        .build();
+    consumer.init().await.context("Failed to initialize consumer")?;
+
+    // Sent right away, a result is not held in a batch
+    let producer = |topic: &str| -> Result<IggyProducer> {
+        Ok(client.producer(stream, topic).context("Failed to create producer")?.without_send_interval().build())
+    };
+    let mut results = producer(&iggy.result_topic)?;
+    results.init().await.context("Failed to initialize producer")?;
+    let mut dead_letters = producer(&iggy.dead_letter_topic)?;
+    dead_letters.init().await.context("Failed to initialize producer")?;
+
+    info!(stream = %stream, topic = %iggy.topic, result_topic = %iggy.result_topic, dead_letter_topic = %iggy.dead_letter_topic, "Started consuming messages");
+
+    let mut prune = tokio::time::interval(PRUNE_INTERVAL);
+    prune.set_missed_tick_behavior(MissedTickBehavior::Delay); // Once after a long analysis
+    loop {
+        let message = tokio::select! {
+            _ = prune.tick() => {
+                if let Err(e) = prune_processed_messages(&db, stream, iggy.retention_days) {
+                    warn!("Processed messages of stream {} not pruned: {}", stream, e);
+                }
+                continue;
+            }
+            message = consumer.next() => message,
+        };
+        let Some(message) = message else {
+            break;
+        };
+        let message = match message {
+            Ok(message) => message,
+            Err(e) => {
+                error!("Failed to poll stream {}: {}", stream, e);
+                continue;
+            }
+        };
+        // Stops the consumer without committing, the request is read again at the next start
+        handle_message(queue, &db, &iggy, stream, tenant, &message, &results, &dead_letters)
+            .await
+            .with_context(|| format!("Message at offset {} of partition {} not processed", message.current_offset, message.partition_id))?;
+        consumer
+            .store_offset(message.current_offset, Some(message.partition_id))
+            .await
+            .with_context(|| format!("Failed to commit offset {} of stream {}", message.current_offset, stream))?;
+    }
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_message(
+    queue: &JobQueue,
+    db: &sled::Db,
+    iggy: &IggyConfig,
+    stream: &str,
+    tenant: &str,
+    message: &ReceivedMessage,
+    results: &IggyProducer,
+    dead_letters: &IggyProducer,
+) -> Result<()> {
+    let payload = &message.message.payload;
+    let request_id = request_id(payload, message.message.id);
+
+    let mut processed = match load_processed_message(db, stream, &request_id).map_err(|e| anyhow!(e))? {
+        Some(processed) if processed.published => {
+            info!(request_id = %request_id, stream = %stream, "Duplicate message skipped");
+            return Ok(());
+        }
+        // Processed before a stop, only its publication is missing
+        Some(processed) => processed,
+        None => {
+            let (result, attempts) = process_with_retries(queue, tenant, &request_id, payload).await;
+            let processed = ProcessedMessage { result, attempts, dead_lettered: false, published: false };
+            save_processed_message(db, stream, &processed).await.map_err(|e| anyhow!(e))?;
+            processed
+        }
+    };
+
+    if processed.result.status == MessageStatus::Failed && !processed.dead_lettered {
+        let dead_letter = DeadLetter {
+            request_id: request_id.clone(),
+            stream: stream.to_string(),
+            topic: iggy.topic.clone(),
+            partition_id: message.partition_id,
+            offset: message.current_offset,
+            attempts: processed.attempts,
+            error: processed.result.error.clone(),
+            payload: String::from_utf8_lossy(payload).into_owned(),
+            failed_at: processed.result.processed_at,
+        };
+        publish_message(dead_letters, iggy, &iggy.dead_letter_topic, &dead_letter).await?;
+        warn!(request_id = %request_id, attempts = processed.attempts, "Message sent to {}", iggy.dead_letter_topic);
+        processed.dead_lettered = true;
+        save_processed_message(db, stream, &processed).await.map_err(|e| anyhow!(e))?;
+    }
+
+    publish_message(results, iggy, &iggy.result_topic, &processed.result).await?;
+    info!(request_id = %request_id, status = ?processed.result.status, "Result published");
+    processed.published = true;
+    save_processed_message(db, stream, &processed).await.map_err(|e| anyhow!(e))?;
+    Ok(())
+}
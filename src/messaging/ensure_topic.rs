@@ -0,0 +1,38 @@
+use anyhow::{Context, Result};
+use iggy::client::{StreamClient, TopicClient};
+use iggy::clients::client::IggyClient;
+use iggy::compression::compression_algorithm::CompressionAlgorithm;
+use iggy::identifier::Identifier;
+use iggy::utils::expiry::IggyExpiry;
+use iggy::utils::topic_size::MaxTopicSize;
+
+// The consumer needs the stream and topic to exist, they are created with one partition and the server defaults
+pub async fn ensure_topic(client: &IggyClient, stream: &str, topic: &str) -> Result<()> {
+    let stream_id = Identifier::named(stream)?;
+    if client.get_stream(&stream_id).await?.is_none() {
+        client
+            .create_stream(stream, None)
+            .await
+            .with_context(|| format!("Failed to create stream {}", stream))?;
+        tracing::info!("Stream {} created", stream);
+    }
+
+    let topic_id = Identifier::named(topic)?;
+    if client.get_topic(&stream_id, &topic_id).await?.is_none() {
+        client
+            .create_topic(
+                &stream_id,
+                topic,
+                1,
+                CompressionAlgorithm::default(),
+                None,
+                None,
+                IggyExpiry::ServerDefault,
+                MaxTopicSize::ServerDefault,
+            )
+            .await
+            .with_context(|| format!("Failed to create topic {} of stream {}", topic, stream))?;
+        tracing::info!("Topic {} of stream {} created", topic, stream);
+    }
+    Ok(())
+}
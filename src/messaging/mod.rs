@@ -0,0 +1,83 @@
+pub mod connect_iggy;
+pub mod consume_messages;
+pub mod ensure_topic;
+pub mod process_message;
+pub mod process_with_retries;
+pub mod processed_messages;
+pub mod publish_message;
+pub mod run_consumer;
+pub mod start_consumers;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::models::AnalysisError;
+
+// Request read from the `topic` of a stream
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MessagePayload {
+    #[serde(default)]
+    pub request_id: Option<String>, // Id of the request for the de-duplication, the id of the Iggy message when not set
+    pub timestamp: String,
+    pub action: String,
+    #[serde(default)]
+    pub parameters: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MessageStatus {
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// Published on the `result_topic` of the stream of the request, once it is processed
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MessageResult {
+    pub request_id: String,
+    pub tenant: Option<String>,
+    pub action: String,
+    pub parameters: Vec<String>,
+    pub status: MessageStatus,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<ProjectResult>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<AnalysisError>,
+    pub processed_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub transient: bool, // The failure may not happen again, the request is worth another attempt
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProjectResult {
+    pub project: String,
+    pub repositories: Vec<Value>, // Reports as written to the output folder
+}
+
+// Published on the `dead_letter_topic` of the stream for a request that failed, with the message as received
+#[derive(Debug, Serialize)]
+pub struct DeadLetter {
+    pub request_id: String,
+    pub stream: String,
+    pub topic: String,
+    pub partition_id: u32,
+    pub offset: u64,
+    pub attempts: u32,
+    pub error: Option<AnalysisError>,
+    pub payload: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+// Id of a request: its `request_id` when set, the id of the Iggy message otherwise
+pub fn request_id(payload: &[u8], message_id: u128) -> String {
+    serde_json::from_slice::<Value>(payload)
+        .ok()
+        .and_then(|payload| payload.get("request_id")?.as_str().map(str::to_string))
+        .filter(|id| !id.is_empty())
+        .unwrap_or_else(|| message_id.to_string())
+}
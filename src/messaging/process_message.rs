@@ -0,0 +1,110 @@
+use chrono::Utc;
+use tracing::{error, info};
+
+use crate::boot::config_for_tenant::config_for_tenant;
+use crate::cli::ScanTarget;
+use crate::jobs::job_queue::{JobPriority, JobQueue, ScanRequest};
+use crate::messaging::{MessagePayload, MessageResult, MessageStatus, ProjectResult};
+use crate::models::AnalysisError;
+use crate::roadmap::reload_roadmaps::reload_roadmaps;
+use crate::types::{CustomError, MyError};
+
+// Actions of a request message
+const ACTIONS: [&str; 5] = ["analyze_specific_repository", "analyze_repository", "analyze_project", "analyze_all", "reload_roadmap"];
+
+/// Run the action of a request message with the configuration of the tenant of its stream.
+/// Analyses go through the job queue like the gRPC requests. Every failure ends up in the result.
+pub async fn process_message(queue: &JobQueue, tenant: &str, request_id: &str, payload: &[u8]) -> MessageResult {
+    let payload = serde_json::from_slice::<MessagePayload>(payload);
+    let (action, parameters) = match &payload {
+        Ok(payload) => (payload.action.clone(), payload.parameters.clone()),
+        Err(_) => (String::new(), vec![]),
+    };
+    let mut result = MessageResult {
+        request_id: request_id.to_string(),
+        tenant: Some(tenant.to_string()).filter(|tenant| !tenant.is_empty()),
+        action,
+        parameters,
+        status: MessageStatus::Completed,
+        message: String::new(),
+        job_id: None,
+        projects: vec![],
+        error: None,
+        processed_at: Utc::now(),
+        transient: false,
+    };
+
+    let outcome = match payload {
+        Ok(payload) => {
+            info!(request_id = %result.request_id, tenant = %tenant, action = %payload.action, parameters = ?payload.parameters, "Message received");
+            run_action(queue, tenant, &payload, &mut result).await
+        }
+        Err(e) => Err(CustomError::parse("request message", e)),
+    };
+    if let Err(e) = outcome {
+        error!(request_id = %result.request_id, error = %e, "Message failed");
+        result.status = MessageStatus::Failed;
+        result.message = e.to_string();
+        result.error = Some(AnalysisError::new(None, &e));
+        result.transient = CustomError::is_transient(e.as_ref());
+    }
+    result.processed_at = Utc::now();
+    result
+}
+
+async fn run_action(queue: &JobQueue, tenant: &str, payload: &MessagePayload, result: &mut MessageResult) -> Result<(), MyError> {
+    let name = |what: &str| {
+        payload
+            .parameters
+            .first()
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .ok_or_else(|| CustomError::invalid_input(format!("The {} is the first parameter of {}", what, payload.action)))
+    };
+    let target = match payload.action.as_str() {
+        "analyze_specific_repository" | "analyze_repository" => ScanTarget::Repo { name: name("repository name")? },
+        "analyze_project" => ScanTarget::Project { name: name("project name")? },
+        "analyze_all" => ScanTarget::All,
+        "reload_roadmap" => {
+            let config = queue.config();
+            let config = config_for_tenant(&config, tenant)?;
+            reload_roadmaps(config).await?;
+            result.message = format!("Roadmap reloaded from {}", config.roadmap_folder);
+            return Ok(());
+        }
+        other => {
+            return Err(CustomError::invalid_input(format!(
+                "Unknown action '{}' (expected one of: {})",
+                other,
+                ACTIONS.join(", ")
+            )))
+        }
+    };
+
+    let request = ScanRequest { target, branch: String::new(), plugins: vec![], projects: vec![], tenant: tenant.to_string() };
+    let job = queue.enqueue(request, JobPriority::OnDemand, "")?;
+    result.job_id = Some(job.id.clone());
+
+    match job.results().await {
+        Some(Ok(results)) => {
+            let mut projects: Vec<ProjectResult> = results
+                .iter()
+                .map(|(project, reports)| ProjectResult { project: project.clone(), repositories: reports.clone() })
+                .collect();
+            projects.sort_by(|a, b| a.project.cmp(&b.project));
+            result.projects = projects;
+            result.message = format!("{} analyzed successfully", job.target);
+        }
+        Some(Err(e)) => {
+            result.status = MessageStatus::Failed;
+            result.message = format!("{} failed: {}", job.target, e);
+            result.error = Some(AnalysisError::new(None, e.as_ref()));
+            result.transient = CustomError::is_transient(e.as_ref().as_ref());
+        }
+        None => {
+            result.status = MessageStatus::Cancelled;
+            result.message = format!("Job {} was cancelled", job.id);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,25 @@
+use tracing::warn;
+
+use crate::jobs::job_queue::JobQueue;
+use crate::messaging::process_message::process_message;
+use crate::messaging::{MessageResult, MessageStatus};
+use crate::utils::http_policy::backoff_delay;
+
+/// Process a request again after a transient failure, at most `iggy.max_retries` times with exponential backoff.
+/// Returns the last result and the number of attempts.
+pub async fn process_with_retries(queue: &JobQueue, tenant: &str, request_id: &str, payload: &[u8]) -> (MessageResult, u32) {
+    let iggy = queue.config().iggy.clone();
+
+    let mut attempt = 0;
+    loop {
+        let result = process_message(queue, tenant, request_id, payload).await;
+        attempt += 1;
+        if result.status != MessageStatus::Failed || !result.transient || attempt > iggy.max_retries {
+            return (result, attempt);
+        }
+
+        let delay = backoff_delay(iggy.initial_backoff_ms, iggy.max_backoff_ms, attempt - 1);
+        warn!("Retrying message {} in {:?} (attempt {}/{}): {}", request_id, delay, attempt, iggy.max_retries, result.message);
+        tokio::time::sleep(delay).await;
+    }
+}
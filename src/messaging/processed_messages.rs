@@ -0,0 +1,74 @@
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::messaging::MessageResult;
+use crate::types::{CustomError, MyError};
+
+const PROCESSED_MESSAGES_TREE: &str = "iggy_messages";
+
+// Result of a request, kept `iggy.retention_days` so a request delivered again is not processed twice
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessedMessage {
+    pub result: MessageResult,
+    pub attempts: u32,
+    pub dead_lettered: bool,
+    pub published: bool,
+}
+
+fn message_key(stream: &str, request_id: &str) -> String {
+    format!("{}/{}", stream, request_id)
+}
+
+fn open_tree(db: &sled::Db) -> Result<sled::Tree, MyError> {
+    db.open_tree(PROCESSED_MESSAGES_TREE).map_err(|e| {
+        Box::new(CustomError::Database { context: format!("Failed to open the {} tree", PROCESSED_MESSAGES_TREE), source: e })
+            as MyError
+    })
+}
+
+pub fn load_processed_message(db: &sled::Db, stream: &str, request_id: &str) -> Result<Option<ProcessedMessage>, MyError> {
+    let tree = open_tree(db)?;
+
+    match tree.get(message_key(stream, request_id).as_bytes())? {
+        Some(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(processed) => Ok(Some(processed)),
+            Err(e) => {
+                warn!("Ignoring unreadable record of message {} of stream {}: {}", request_id, stream, e);
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+// Flushed, the record must be on disk before the offset is committed
+pub async fn save_processed_message(db: &sled::Db, stream: &str, processed: &ProcessedMessage) -> Result<(), MyError> {
+    let tree = open_tree(db)?;
+
+    tree.insert(message_key(stream, &processed.result.request_id).as_bytes(), serde_json::to_vec(processed)?)?;
+    tree.flush_async().await?;
+    Ok(())
+}
+
+// Records of the stream older than the retention, a request is only de-duplicated within it
+pub fn prune_processed_messages(db: &sled::Db, stream: &str, retention_days: u64) -> Result<(), MyError> {
+    let tree = open_tree(db)?;
+    let oldest = Utc::now() - Duration::days(retention_days.min(36_500) as i64);
+
+    let mut removed = 0;
+    for entry in tree.scan_prefix(message_key(stream, "").as_bytes()) {
+        let (key, bytes) = entry?;
+        let expired = serde_json::from_slice::<ProcessedMessage>(&bytes)
+            .map(|processed| processed.published && processed.result.processed_at < oldest)
+            .unwrap_or(true);
+        if expired {
+            tree.remove(key)?;
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        info!("{} processed messages of stream {} forgotten", removed, stream);
+    }
+    Ok(())
+}
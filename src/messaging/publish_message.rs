@@ -0,0 +1,29 @@
+use anyhow::{Context, Result};
+use iggy::clients::producer::IggyProducer;
+use iggy::messages::send_messages::Message;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::models::IggyConfig;
+use crate::utils::http_policy::backoff_delay;
+
+/// Publish a JSON message, retried `iggy.max_retries` times with exponential backoff.
+/// The offset of the request is not committed when it fails, the request is read again at the next start.
+pub async fn publish_message<T: Serialize>(producer: &IggyProducer, iggy: &IggyConfig, topic: &str, value: &T) -> Result<()> {
+    let payload = serde_json::to_string(value).context("Failed to serialize the message")?;
+
+    let mut attempt = 0;
+    loop {
+        let message = payload.parse::<Message>()?;
+        match producer.send_one(message).await {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt < iggy.max_retries => {
+                let delay = backoff_delay(iggy.initial_backoff_ms, iggy.max_backoff_ms, attempt);
+                attempt += 1;
+                warn!("Retrying publication on {} in {:?} (attempt {}/{}): {}", topic, delay, attempt, iggy.max_retries, e);
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to publish on {}", topic)),
+        }
+    }
+}
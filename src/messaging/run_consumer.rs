@@ -0,0 +1,35 @@
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+use crate::jobs::job_queue::JobQueue;
+use crate::messaging::connect_iggy::connect_iggy;
+use crate::messaging::consume_messages::consume_messages;
+use crate::utils::http_policy::backoff_delay;
+
+/// Consumer of one stream for the lifetime of the server. A lost connection or a stopped consumer is started again
+/// on a new connection, after an exponential backoff that starts over once a consumer ran longer than `max_backoff_ms`.
+pub async fn run_consumer(queue: JobQueue, stream: String, tenant: String) {
+    let mut failures = 0;
+    loop {
+        let iggy = queue.config().iggy.clone();
+        let started = Instant::now();
+        match connect_iggy(&iggy).await {
+            Ok(client) => {
+                info!(address = %iggy.address, stream = %stream, "Connected to Iggy");
+                match consume_messages(&queue, &client, &stream, &tenant).await {
+                    Ok(()) => warn!("Consumer of stream {} ended", stream),
+                    Err(e) => error!("Consumer of stream {} stopped: {:#}", stream, e),
+                }
+            }
+            Err(e) => error!("Consumer of stream {} not started: {:#}", stream, e),
+        }
+
+        if started.elapsed() > Duration::from_millis(iggy.max_backoff_ms) {
+            failures = 0;
+        }
+        let delay = backoff_delay(iggy.initial_backoff_ms, iggy.max_backoff_ms, failures);
+        failures = failures.saturating_add(1);
+        info!("Restarting the consumer of stream {} in {:?}", stream, delay);
+        tokio::time::sleep(delay).await;
+    }
+}
@@ -0,0 +1,13 @@
+use crate::jobs::job_queue::JobQueue;
+use crate::messaging::run_consumer::run_consumer;
+
+// One consumer for the stream of the top level configuration and one for the stream of each tenant, each on its own connection.
+// Tenants added by a reload are consumed after a restart.
+pub fn start_consumers(queue: JobQueue) {
+    let config = queue.config();
+    let streams = std::iter::once((config.iggy.stream.clone(), String::new()))
+        .chain(config.tenants.keys().map(|tenant| (tenant.clone(), tenant.clone())));
+    for (stream, tenant) in streams {
+        tokio::spawn(run_consumer(queue.clone(), stream, tenant));
+    }
+}
@@ -1,18 +1,29 @@
 use chrono::NaiveDate;
 // use reqwest::blocking::Client;
+use regex::Regex;
 use reqwest::Client;
 use serde::{Serialize, Deserialize, Serializer, ser::SerializeStruct};
 use sled::Db;
 use std::collections::HashMap;
 
+use crate::auth::ApiToken;
+use crate::credentials::Credentials;
+use crate::jobs::Job;
 use crate::url::bitbucket::BitbucketConfig;
+use crate::utils::http_policy::{CircuitBreakers, HttpStats};
 use crate::url::UrlConfig;
+use crate::types::{error_chain, CustomError, MyError};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct ConfigFile {
+    #[serde(default)]
     pub platform: String,
+    #[serde(default)]
     pub base_url: String,
     pub user: Option<String>, // Only for GitHub
+    pub credentials: Option<CredentialsConfig>,
+    #[serde(default)]
+    pub sources: Vec<SourceConfig>, // Replaces platform/base_url/user/github/bitbucket_cloud when set
     pub force_git_pull: bool,
     pub force_maven_effective: bool,
     pub force_sled_db_sourcing: bool,
@@ -27,12 +38,361 @@ pub struct ConfigFile {
     pub enable_dotnet_analysis: bool,
     pub enable_php_analysis: bool,
     pub enable_jenkins_analysis: bool,
+    #[serde(default)]
+    pub enable_ci_analysis: bool,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub incremental: bool,
+    #[serde(default)]
+    pub github: GithubScopes,
+    #[serde(default)]
+    pub bitbucket_cloud: BitbucketCloudScopes,
+    #[serde(default = "default_concurrency")]
+    pub concurrency: usize, // Repositories analysed at the same time
+    #[serde(default)]
+    pub policy: PolicyConfig,
+    #[serde(default)]
+    pub jobs: Vec<JobConfig>, // Run without a terminal, or with `xdebt run`
+    #[serde(default)]
+    pub schedules: Vec<ScheduleConfig>, // Queued by `xdebt serve`
+    #[serde(default)]
+    pub rest: RestConfig,
+    #[serde(default)]
+    pub grpc: GrpcConfig,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub iggy: IggyConfig,
+    #[serde(default)]
+    pub tenants: HashMap<String, TenantConfig>, // Selected by the `tenant` of a request, the top level configuration otherwise
+}
+
+fn default_concurrency() -> usize {
+    1
+}
+
+// Command-line values taking precedence over the configuration file, kept on reload
+#[derive(Debug, Clone, Default)]
+pub struct ConfigOverrides {
+    pub trace_level: Option<String>,
+    pub output_folder: Option<String>,
+    pub concurrency: Option<usize>,
+}
+
+impl ConfigOverrides {
+    pub fn apply(&self, config: &mut ConfigFile) {
+        if let Some(trace_level) = &self.trace_level {
+            config.trace_level = trace_level.clone();
+        }
+        if let Some(output_folder) = &self.output_folder {
+            config.output_folder = output_folder.clone();
+        }
+        if let Some(concurrency) = self.concurrency {
+            config.concurrency = concurrency;
+        }
+    }
+}
+
+// Keys of a tenant replacing the top level ones, the others are shared.
+// Each tenant has its own roadmap database and its results under `<output_folder>-tenants/<tenant>` by default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TenantConfig {
+    pub platform: Option<String>,
+    pub base_url: Option<String>,
+    pub user: Option<String>,
+    pub credentials: Option<CredentialsConfig>,
+    pub sources: Option<Vec<SourceConfig>>, // Replaces platform/base_url/user/credentials when set
+    pub github: Option<GithubScopes>,
+    pub bitbucket_cloud: Option<BitbucketCloudScopes>,
+    pub equivalences: Option<HashMap<String, Vec<String>>>,
+    pub roadmap_folder: Option<String>,
+    pub output_folder: Option<String>,
+}
+
+impl TenantConfig {
+    // Next to the top level output folder and not inside it, its results must not be served to other callers
+    pub fn output_folder(&self, name: &str, top_output_folder: &str) -> String {
+        match &self.output_folder {
+            Some(output_folder) => output_folder.clone(),
+            None => format!("{}-tenants/{}", top_output_folder.trim_end_matches('/'), name),
+        }
+    }
+
+    // Top level configuration of the tenant, overrides of the command line already applied
+    pub fn apply(&self, name: &str, config: &mut ConfigFile) {
+        // A tenant with its own platform keys does not scan the top level sources,
+        // its single source is built from the merged keys like a configuration without `sources`
+        if self.platform.is_some()
+            || self.base_url.is_some()
+            || self.user.is_some()
+            || self.credentials.is_some()
+            || self.github.is_some()
+            || self.bitbucket_cloud.is_some()
+        {
+            config.sources = vec![];
+        }
+        if let Some(platform) = &self.platform {
+            config.platform = platform.clone();
+        }
+        if let Some(base_url) = &self.base_url {
+            config.base_url = base_url.clone();
+        }
+        if self.user.is_some() {
+            config.user = self.user.clone();
+        }
+        if self.credentials.is_some() {
+            config.credentials = self.credentials.clone();
+        }
+        if let Some(sources) = &self.sources {
+            config.sources = sources.clone();
+        }
+        if let Some(github) = &self.github {
+            config.github = github.clone();
+        }
+        if let Some(bitbucket_cloud) = &self.bitbucket_cloud {
+            config.bitbucket_cloud = bitbucket_cloud.clone();
+        }
+        if let Some(equivalences) = &self.equivalences {
+            config.equivalences = equivalences.clone();
+        }
+        if let Some(roadmap_folder) = &self.roadmap_folder {
+            config.roadmap_folder = roadmap_folder.clone();
+        }
+        config.output_folder = self.output_folder(name, &config.output_folder);
+        config.tenants = HashMap::new();
+    }
+}
+
+// One scan of the headless job list, `name` is required for `repo` and `project`
+#[derive(Debug, Clone, Deserialize)]
+pub struct JobConfig {
+    pub scan: JobScope,
+    pub name: Option<String>,
+    pub tenant: Option<String>, // Top level configuration when not set
+}
+
+// HTTP/JSON API started by `xdebt serve` next to the gRPC server
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct RestConfig {
+    pub enabled: bool,
+    pub address: String,
+}
+
+impl Default for RestConfig {
+    fn default() -> Self {
+        RestConfig { enabled: false, address: "0.0.0.0:8080".to_string() }
+    }
+}
+
+// gRPC server of `xdebt serve`, plaintext when `tls` is not set
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct GrpcConfig {
+    pub address: String,
+    pub tls: Option<TlsConfig>,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        GrpcConfig { address: "0.0.0.0:50052".to_string(), tls: None }
+    }
+}
+
+// Iggy consumer of `xdebt serve`: requests read from `topic`, results published on `result_topic` of the same stream.
+// `stream` is the one of the top level configuration, each tenant has the stream named after it.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct IggyConfig {
+    pub enabled: bool,
+    pub address: String, // TCP address of the Iggy server
+    pub username: String,
+    pub password_env: Option<String>, // Default password of the Iggy server when not set
+    pub stream: String,
+    pub topic: String,
+    pub result_topic: String,
+    pub consumer_group: String,
+    pub dead_letter_topic: String, // Requests that failed, after the retries of the transient failures
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub retention_days: u64, // Ids of the processed requests are forgotten after it
+}
+
+impl Default for IggyConfig {
+    fn default() -> Self {
+        IggyConfig {
+            enabled: false,
+            address: "127.0.0.1:8090".to_string(),
+            username: "iggy".to_string(),
+            password_env: None,
+            stream: "xdebt".to_string(),
+            topic: "requests".to_string(),
+            result_topic: "results".to_string(),
+            consumer_group: "xdebt".to_string(),
+            dead_letter_topic: "dead_letters".to_string(),
+            max_retries: 3,
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 60_000,
+            retention_days: 30,
+        }
+    }
+}
+
+// PEM files, `client_ca` turns on mutual TLS: clients need a certificate signed by it
+#[derive(Debug, Clone, Deserialize)]
+pub struct TlsConfig {
+    pub cert: String,
+    pub key: String,
+    pub client_ca: Option<String>,
+}
+
+// Callers of the gRPC and REST servers, anyone may call them when no token is declared
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    pub tokens: Vec<ApiTokenConfig>,
+}
+
+// Bearer token of a caller, read from `token_env` or `token_file`
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiTokenConfig {
+    pub name: String,
+    pub token_env: Option<String>,
+    pub token_file: Option<String>,
+    #[serde(default)]
+    pub projects: Vec<String>, // Projects the caller may scan, every project when empty
+    #[serde(default)]
+    pub tenants: Vec<String>, // Tenants the caller may use, every tenant when empty
+}
+
+// Recurring scan of the server, `cron` is minute hour day-of-month month day-of-week in local time
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScheduleConfig {
+    pub cron: String,
+    #[serde(flatten)]
+    pub job: JobConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobScope {
+    Repo,
+    Project,
+    All,
+}
+
+// Thresholds of a scan, breached when a count is above its maximum (no limit when not set)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct PolicyConfig {
+    pub max_outdated: Option<usize>,
+    pub max_non_compliant: Option<usize>,
+    pub max_failed: Option<usize>, // Repositories whose analysis failed
+}
+
+// One platform to scan, results of every source are consolidated in the same report
+#[derive(Debug, Clone, Deserialize)]
+pub struct SourceConfig {
+    pub name: String, // Tags the repositories of this source in the results
+    pub platform: String,
+    pub base_url: String,
+    pub user: Option<String>, // Only for GitHub
+    pub env_prefix: Option<String>, // Default variables become <PREFIX>_GITHUB_TOKEN, <PREFIX>_BITBUCKET_USERNAME...
+    pub credentials: Option<CredentialsConfig>, // Environment variables when not set
+    #[serde(default)]
+    pub github: GithubScopes,
+    #[serde(default)]
+    pub bitbucket_cloud: BitbucketCloudScopes,
+    #[serde(default)]
+    pub projects: Vec<String>, // Only these projects (Bitbucket keys, GitHub orgs/teams, workspaces) when set
+    #[serde(default)]
+    pub exclude_repos: Vec<String>, // Regexes of repository names to skip
+    #[serde(skip)]
+    pub exclude_patterns: Vec<Regex>, // `exclude_repos` compiled once at boot
+}
+
+// Where the credentials of a source come from
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "provider", rename_all = "snake_case")]
+pub enum CredentialsConfig {
+    // Process environment and `.env`, a token wins over username/password
+    Env {
+        token_env: Option<String>,
+        username_env: Option<String>,
+        password_env: Option<String>,
+    },
+    // Variables of a dotenv file, not exported to the environment
+    Dotenv {
+        path: String,
+        token_env: Option<String>,
+        username_env: Option<String>,
+        password_env: Option<String>,
+    },
+    // Login/password of the base_url host, ~/.netrc by default
+    Netrc { path: Option<String> },
+    // File-mounted secret: a token, or the password of `username`
+    File { path: String, username: Option<String> },
+    // Output of a command (vault, pass, cloud CLI): a token, or the password of `username`
+    Command { credential_command: String, username: Option<String> },
+}
+
+// Which GitHub repositories are scanned and under which xdebt project they are reported
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct GithubScopes {
+    pub orgs: Vec<String>,  // Every repository of these organisations
+    pub teams: Vec<String>, // "org/team-slug", repositories of an organisation team
+    pub topics: Vec<String>, // When set, only repositories with one of these topics
+    pub projects: HashMap<String, String>, // Organisation or topic -> xdebt project
+}
+
+// Which bitbucket.org workspaces are scanned and under which xdebt project they are reported
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct BitbucketCloudScopes {
+    pub workspaces: Vec<String>, // Every workspace of the credentials when empty
+    pub projects: HashMap<String, String>, // Workspace or workspace project key -> xdebt project
+}
+
+// Timeouts, retries and circuit breaker applied to every outgoing HTTP call
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    pub timeout_secs: u64,
+    pub connect_timeout_secs: u64,
+    pub max_retries: u32,
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    pub circuit_breaker_threshold: u32, // Consecutive failures before a host is skipped
+    pub circuit_breaker_cooldown_secs: u64,
+    pub cache_enabled: bool, // Keep responses in sled and revalidate them with ETag/Last-Modified
+    pub cache_ttl_days: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        HttpConfig {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+            max_retries: 3,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            circuit_breaker_threshold: 5,
+            circuit_breaker_cooldown_secs: 60,
+            cache_enabled: true,
+            cache_ttl_days: 30,
+        }
+    }
 }
 
 // Custom deserialization function for `url_config`
 fn default_url_config() -> Box<dyn UrlConfig> {
     Box::new(BitbucketConfig {
         base_url: "https://bitbucket.org".to_string(),
+        credentials: Credentials::Anonymous,
     })
 }
 
@@ -56,6 +416,30 @@ pub struct AppConfig {
     pub enable_dotnet_analysis: bool,
     pub enable_php_analysis: bool,
     pub enable_jenkins_analysis: bool,
+    pub enable_ci_analysis: bool,
+    pub http: HttpConfig,
+    pub http_stats: Arc<HttpStats>,
+    pub circuit_breakers: Arc<CircuitBreakers>,
+    pub head_commit: Option<String>, // Head commit of the repository being analysed, set per repository
+    pub branch: Option<String>, // Branch analysed, the default one when not set
+    pub plugins_selected: bool, // Plugins chosen by the request instead of the configured ones
+    pub job: Option<Arc<Job>>, // Job receiving the progress events, set for background scans
+    pub incremental: bool,
+    pub github: GithubScopes,
+    pub bitbucket_cloud: BitbucketCloudScopes,
+    pub sources: Vec<SourceConfig>,
+    pub source: Option<SourceConfig>, // Source being scanned, set per source
+    pub credentials: HashMap<String, Credentials>, // Resolved at boot, by source name
+    pub concurrency: usize,
+    pub policy: PolicyConfig,
+    pub jobs: Vec<JobConfig>,
+    pub schedules: Vec<ScheduleConfig>,
+    pub rest: RestConfig,
+    pub grpc: GrpcConfig,
+    pub iggy: IggyConfig,
+    pub api_tokens: Vec<ApiToken>, // Resolved at boot from `auth`
+    pub tenant: Option<String>, // Tenant of this configuration, the top level one when not set
+    pub tenants: HashMap<String, AppConfig>, // Configuration of each tenant, with its own database
 }
 
 // Manually implement Default for AppConfig
@@ -79,6 +463,30 @@ impl Default for AppConfig {
             enable_dotnet_analysis: false,
             enable_php_analysis: false,
             enable_jenkins_analysis: false,
+            enable_ci_analysis: false,
+            http: HttpConfig::default(),
+            http_stats: Arc::new(HttpStats::default()),
+            circuit_breakers: Arc::new(CircuitBreakers::default()),
+            head_commit: None,
+            branch: None,
+            plugins_selected: false,
+            job: None,
+            incremental: false,
+            github: GithubScopes::default(),
+            bitbucket_cloud: BitbucketCloudScopes::default(),
+            sources: vec![],
+            source: None,
+            credentials: HashMap::new(),
+            concurrency: 1,
+            policy: PolicyConfig::default(),
+            jobs: vec![],
+            schedules: vec![],
+            rest: RestConfig::default(),
+            grpc: GrpcConfig::default(),
+            iggy: IggyConfig::default(),
+            api_tokens: vec![],
+            tenant: None,
+            tenants: HashMap::new(),
         }
     }
 }
@@ -88,7 +496,7 @@ pub struct Roadmaps {
     pub roadmap_list: Vec<Roadmap>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Roadmap {
     pub product: String,
     pub domain: Option<String>,
@@ -106,17 +514,39 @@ pub struct RoadmapEntry {
     pub source_name: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DependencyVersion {
     pub cycle: String,
     pub product: String,
 }
 
-#[derive(Debug, Deserialize)]
+// Where a dependency version was found, so a finding can be traced back to its source
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Evidence {
+    pub plugin: String,
+    pub file_path: Option<String>,
+    pub line: Option<usize>,
+    pub artifact: Option<String>,
+    pub matched_alias: Option<String>,
+    pub url: Option<String>,
+}
+
+impl Evidence {
+    pub fn new(plugin: &str, file_path: &str) -> Self {
+        Evidence {
+            plugin: plugin.to_string(),
+            file_path: Some(file_path.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone)]
 pub struct Analysis {
     pub repository_name: String,
     pub dependency_version: DependencyVersion,
     pub roadmap: Option<Roadmap>,
+    pub evidence: Evidence,
 }
 
 impl Serialize for Analysis {
@@ -125,18 +555,19 @@ impl Serialize for Analysis {
         S: Serializer,
     {
         // Define the number of fields to serialize
-        let mut state = serializer.serialize_struct("Analysis", 3)?;
+        let mut state = serializer.serialize_struct("Analysis", 4)?;
 
         // Serialize the fields in the custom order
         state.serialize_field("repository_name", &self.repository_name)?;
         state.serialize_field("dependency_version", &self.dependency_version)?;
         state.serialize_field("roadmap", &self.roadmap)?;
+        state.serialize_field("evidence", &self.evidence)?;
 
         state.end()
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum KPIStatus {
     Compliant,
     NonCompliant,
@@ -145,6 +576,19 @@ pub enum KPIStatus {
     NoActionNeeded,
 }
 
+impl KPIStatus {
+    // Higher is worse, used to pick the worst/best occurrence of a product
+    pub fn severity(&self) -> u8 {
+        match self {
+            KPIStatus::UpToDate => 0,
+            KPIStatus::NoActionNeeded => 1,
+            KPIStatus::Compliant => 2,
+            KPIStatus::NonCompliant => 3,
+            KPIStatus::Outdated => 4,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KPIResult {
     pub product: String,
@@ -153,6 +597,155 @@ pub struct KPIResult {
     pub reason: String,
     pub source: Option<String>,
     pub validity: Option<String>,
+    pub evidence: Evidence,
 }
 
+// Verdict over every occurrence of a product in one repository
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductVerdict {
+    pub product: String,
+    pub worst: KPIStatus,
+    pub worst_cycle: String,
+    pub best: KPIStatus,
+    pub best_cycle: String,
+    pub cycles: Vec<String>, // Distinct versions found, more than one means version skew
+    pub occurrences: usize,
+}
+
+// A failure met while analysing a repository, reported next to the findings
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnalysisError {
+    pub plugin: Option<String>,
+    pub kind: String,
+    pub message: String,
+}
+
+impl AnalysisError {
+    pub fn new(plugin: Option<&str>, err: &MyError) -> Self {
+        AnalysisError {
+            plugin: plugin.map(str::to_string),
+            kind: CustomError::kind(err.as_ref()).to_string(),
+            message: error_chain(err.as_ref()),
+        }
+    }
+}
+
+// `Incomplete` means some plugins failed, so missing debt is not proof of no debt
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisStatus {
+    Complete,
+    Incomplete,
+    Failed,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepositoryReport {
+    pub application: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub source: String,
+    pub status: AnalysisStatus,
+    pub debt: Vec<KPIResult>,
+    pub products: Vec<ProductVerdict>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<AnalysisError>,
+    pub head_commit: Option<String>,
+    #[serde(default)]
+    pub reused: bool, // Findings reused from the previous scan of the same commit
+}
+
+// One KPI of the last results with its roadmap classification, as shown by the dashboard
+#[derive(Debug, Serialize)]
+pub struct DashboardRow {
+    pub project: String,
+    pub repository: String,
+    pub repository_status: AnalysisStatus,
+    pub product: String,
+    pub cycle: String,
+    pub status: KPIStatus,
+    pub reason: String,
+    pub domain: Option<String>,
+    pub chapter: Option<String>,
+    pub eol: Option<NaiveDate>, // End of life of the cycle, the extended one when there is no other
+    pub days_past_eol: Option<i64>, // Not set before the end of life or when it is unknown
+    pub evidence: Evidence,
+}
+
+// Counts of a scan checked against the policy thresholds
+#[derive(Debug, Default, Serialize)]
+pub struct PolicyReport {
+    pub repositories: usize,
+    pub outdated: usize,
+    pub non_compliant: usize,
+    pub failed: usize,
+    pub breaches: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    const CONFIG: &str = r#"
+platform: bitbucket
+base_url: https://bitbucket.example.com
+sources:
+  - name: cloud
+    platform: github
+    base_url: https://api.github.com
+force_git_pull: false
+force_maven_effective: false
+force_sled_db_sourcing: false
+trace_level: INFO
+output_folder: out
+roadmap_folder: roadmap_db
+sources_priorities: ~
+equivalences: {}
+enable_maven_analysis: true
+enable_npm_analysis: true
+enable_docker_analysis: true
+enable_dotnet_analysis: true
+enable_php_analysis: true
+enable_jenkins_analysis: true
+tenants:
+  own_url:
+    base_url: https://bitbucket.mayorana.ch
+  own_github:
+    github:
+      orgs: [mayorana]
+  own_sources:
+    base_url: https://bitbucket.mayorana.ch
+    sources:
+      - name: mayorana
+        platform: github
+        base_url: https://api.github.com
+  own_roadmap:
+    roadmap_folder: mayorana_db
+"#;
+
+    fn tenant_file(name: &str) -> ConfigFile {
+        let mut config: ConfigFile = serde_yaml::from_str(CONFIG).unwrap();
+        let tenant = config.tenants[name].clone();
+        tenant.apply(name, &mut config);
+        config
+    }
+
+    #[test]
+    fn apply_drops_the_top_level_sources_when_a_platform_key_is_overridden() {
+        let config = tenant_file("own_url");
+        assert!(config.sources.is_empty());
+        assert_eq!(config.platform, "bitbucket");
+        assert_eq!(config.base_url, "https://bitbucket.mayorana.ch");
+        assert!(tenant_file("own_github").sources.is_empty());
+    }
+
+    #[test]
+    fn apply_keeps_the_sources_of_the_tenant_or_the_top_level_ones() {
+        let config = tenant_file("own_sources");
+        assert_eq!(config.sources.iter().map(|source| source.name.as_str()).collect::<Vec<_>>(), vec!["mayorana"]);
+
+        let config = tenant_file("own_roadmap");
+        assert_eq!(config.sources.iter().map(|source| source.name.as_str()).collect::<Vec<_>>(), vec!["cloud"]);
+        assert_eq!(config.roadmap_folder, "mayorana_db");
+        assert_eq!(config.output_folder, "out-tenants/own_roadmap");
+        assert!(config.tenants.is_empty());
+    }
+}
@@ -1,9 +1,11 @@
 
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::utils::enrich_versions_with_roadmap::enrich_versions_with_roadmap;
 use crate::services::get_distinct_dependencies::get_distinct_dependencies;
-use crate::models::{AppConfig, Analysis};
+use crate::jobs::ScanEvent;
+use crate::jobs::emit_event::emit_event;
+use crate::models::{AppConfig, Analysis, AnalysisError};
 
 use crate::plugins::php::check_php::check_php;
 use crate::plugins::maven::analyze_maven::analyze_maven;
@@ -11,13 +13,15 @@ use crate::plugins::npm::analyze_npm::analyze_npm;
 use crate::plugins::docker::check_docker::check_docker;
 use crate::plugins::dotnet::check_dotnet::check_dotnet;
 use crate::plugins::jenkins::analyze_jenkins::analyze_jenkins;
-use crate::types::MyError;
+use crate::plugins::ci::analyze_ci::analyze_ci;
+use crate::types::{error_chain, CustomError, MyError};
+use crate::url::UrlMode;
 
 pub async fn analyze_one_repo<'a>(
     config: &'a AppConfig,
     project_name: &'a str,
     repository_name_str: &'a str,
-) -> Result<Vec<Analysis>, MyError> {
+) -> Result<(Vec<Analysis>, Vec<AnalysisError>), MyError> {
     let repository_name = repository_name_str.to_string();
     let db = config.db.as_ref().expect("Db should be initialized");
 
@@ -28,39 +32,106 @@ pub async fn analyze_one_repo<'a>(
     let versions_keywords: Vec<&str> = dependency_names.iter().map(|s| s.as_str()).collect();
 
     let mut analyses = Vec::new();
+    // A failing plugin does not stop the others, its error is reported with the findings
+    let mut errors = Vec::new();
 
     // 1. Maven (POM) Analysis
     if config.enable_maven_analysis {
-        let _ = analyze_maven(config, project_name, repository_name_str, &output_folder, &versions_keywords, &mut analyses).await;
+        let found = analyses.len();
+        let outcome = analyze_maven(config, project_name, repository_name_str, &output_folder, &versions_keywords, &mut analyses).await;
+        record_plugin_outcome(config, "maven", repository_name_str, outcome, &analyses[found..], &mut errors);
     }
 
     // 2. NPM (package.json) Analysis
     if config.enable_npm_analysis {
-        analyze_npm(config, project_name, repository_name_str, &versions_keywords, &mut analyses).await?;
+        let found = analyses.len();
+        let outcome = analyze_npm(config, project_name, repository_name_str, &versions_keywords, &mut analyses).await;
+        record_plugin_outcome(config, "npm", repository_name_str, outcome, &analyses[found..], &mut errors);
     }
 
     // 3. Dockerfile Check
     if config.enable_docker_analysis {
-        check_docker(config, project_name, repository_name_str, &repository_name, &mut analyses).await?;
+        let found = analyses.len();
+        let outcome = check_docker(config, project_name, repository_name_str, &repository_name, &mut analyses).await;
+        record_plugin_outcome(config, "docker", repository_name_str, outcome, &analyses[found..], &mut errors);
     }
 
     // 4. C# (.csproj) Analysis
     if config.enable_dotnet_analysis {
-        check_dotnet(config, project_name, repository_name_str, &repository_name, &mut analyses).await?;
+        let found = analyses.len();
+        let outcome = check_dotnet(config, project_name, repository_name_str, &repository_name, &mut analyses).await;
+        record_plugin_outcome(config, "dotnet", repository_name_str, outcome, &analyses[found..], &mut errors);
     }
 
     // 5. PHP File Check
     if config.enable_php_analysis {
-        check_php(config, project_name, repository_name_str, &repository_name, &mut analyses).await?;
+        let found = analyses.len();
+        let outcome = check_php(config, project_name, repository_name_str, &repository_name, &mut analyses).await;
+        record_plugin_outcome(config, "php", repository_name_str, outcome, &analyses[found..], &mut errors);
     }
 
     // 6. Jenkins File Analysis
     if config.enable_jenkins_analysis {
-        analyze_jenkins(config, project_name, repository_name_str, &versions_keywords, &repository_name, &mut analyses).await?;
+        let found = analyses.len();
+        let outcome = analyze_jenkins(config, project_name, repository_name_str, &versions_keywords, &repository_name, &mut analyses).await;
+        record_plugin_outcome(config, "jenkins", repository_name_str, outcome, &analyses[found..], &mut errors);
+    }
+
+    // 7. CI (GitHub Actions, GitLab CI) Analysis
+    if config.enable_ci_analysis {
+        let found = analyses.len();
+        let outcome = analyze_ci(config, project_name, repository_name_str, &versions_keywords, &repository_name, &mut analyses).await;
+        record_plugin_outcome(config, "ci", repository_name_str, outcome, &analyses[found..], &mut errors);
+    }
+
+    // Deep link every finding to the file (and line) it was found in
+    for analysis in analyses.iter_mut() {
+        if let Some(file_path) = &analysis.evidence.file_path {
+            let mut url = config.url_config.file_url(UrlMode::Browse, project_name, repository_name_str, file_path, config.branch.as_deref());
+            if let Some(line) = analysis.evidence.line {
+                url.push_str(&config.url_config.line_anchor(line));
+            }
+            analysis.evidence.url = Some(url);
+        }
     }
 
     debug!("Final result of analysis for project '{}', repo '{}': {:?}", project_name, repository_name, analyses);
 
     let enriched_analyses = enrich_versions_with_roadmap(db, analyses).await?;
-    Ok(enriched_analyses)
+    Ok((enriched_analyses, errors))
+}
+
+// Progress events of a plugin (its findings, then its end), and its error if any
+fn record_plugin_outcome(
+    config: &AppConfig,
+    plugin: &str,
+    repository: &str,
+    outcome: Result<(), MyError>,
+    found: &[Analysis],
+    errors: &mut Vec<AnalysisError>,
+) {
+    for analysis in found {
+        emit_event(config, || ScanEvent::FindingProduced {
+            repository: repository.to_string(),
+            plugin: plugin.to_string(),
+            product: analysis.dependency_version.product.clone(),
+            cycle: analysis.dependency_version.cycle.clone(),
+            file_path: analysis.evidence.file_path.clone(),
+        });
+    }
+
+    let error = outcome.err().map(|e| {
+        let e = CustomError::plugin(plugin, e);
+        warn!("{}", error_chain(e.as_ref()));
+        let error = AnalysisError::new(Some(plugin), &e);
+        errors.push(error.clone());
+        error.message
+    });
+
+    emit_event(config, || ScanEvent::PluginFinished {
+        repository: repository.to_string(),
+        plugin: plugin.to_string(),
+        findings: found.len(),
+        error,
+    });
 }
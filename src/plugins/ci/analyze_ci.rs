@@ -0,0 +1,80 @@
+
+use tracing::{info, warn};
+
+use crate::plugins::ci::check_ci_files_exist::check_ci_files_exist;
+use crate::plugins::ci::extract_ci_versions::extract_ci_versions;
+use crate::models::{AppConfig, Analysis, DependencyVersion, Evidence};
+use crate::utils::find_line_number::find_line_number;
+use crate::utils::run_get_request::run_get_request;
+use crate::types::MyError;
+
+pub async fn analyze_ci(
+    config: &AppConfig,
+    project_name: &str,
+    repository_name: &str,
+    versions_keywords: &[&str],
+    repository_name_str: &str,
+    analyses: &mut Vec<Analysis>,
+) -> Result<(), MyError> {
+    info!("Start of CI analysis");
+
+    let ci_files = check_ci_files_exist(config, project_name, repository_name).await?;
+    if ci_files.is_empty() {
+        info!("No CI file found for project '{}', repo '{}'.", project_name, repository_name);
+        return Ok(());
+    }
+
+    for (file_path, file_url) in ci_files {
+        let content = run_get_request(config, &file_url).await?.unwrap_or_default();
+
+        let ci_versions = match extract_ci_versions(&content) {
+            Ok(ci_versions) => ci_versions,
+            Err(e) => {
+                warn!("Failed to parse CI file '{}' in repo '{}': {}", file_path, repository_name, e);
+                continue;
+            }
+        };
+
+        for (tool, cycle) in ci_versions {
+            // Map the CI tool (image, action, runtime) to a roadmap product
+            match find_product(config, versions_keywords, &tool) {
+                Some(product) => {
+                    info!("Adding CI version to analysis : {}/{} (from {} in {})", product, cycle, tool, file_path);
+                    let evidence = Evidence {
+                        line: find_line_number(&content, &tool),
+                        artifact: Some(tool.clone()),
+                        matched_alias: Some(tool.clone()),
+                        ..Evidence::new("ci", &file_path)
+                    };
+                    analyses.push(Analysis {
+                        repository_name: repository_name_str.to_string(),
+                        dependency_version: DependencyVersion {
+                            product: product.to_string(),
+                            cycle,
+                        },
+                        roadmap: None,
+                        evidence,
+                    });
+                }
+                None => {
+                    info!("No roadmap product for CI tool '{}' in {}.", tool, file_path);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// A tool matches a product by name or through one of the product equivalences
+fn find_product<'a>(config: &AppConfig, versions_keywords: &[&'a str], tool: &str) -> Option<&'a str> {
+    let tool = tool.to_lowercase();
+    versions_keywords.iter().copied().find(|keyword| {
+        keyword.to_lowercase() == tool
+            || config
+                .equivalences
+                .get(*keyword)
+                .map(|equivalents| equivalents.iter().any(|e| e.to_lowercase() == tool))
+                .unwrap_or(false)
+    })
+}
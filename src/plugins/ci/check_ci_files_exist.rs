@@ -0,0 +1,41 @@
+
+use tracing::{debug, info};
+
+use crate::utils::check_file_exists::check_file_exists;
+use crate::utils::list_directory_files::list_directory_files;
+use crate::models::AppConfig;
+use crate::types::MyError;
+
+/// Returns the (file path, file URL) of every CI definition found in the repository:
+/// GitHub Actions workflows and the GitLab CI file.
+pub async fn check_ci_files_exist(
+    config: &AppConfig,
+    project_name: &str,
+    repo_name: &str,
+) -> Result<Vec<(String, String)>, MyError> {
+    let mut ci_files = Vec::new();
+
+    // GitHub Actions: every YAML file in .github/workflows
+    let workflows_dir = ".github/workflows";
+    for file in list_directory_files(config, project_name, repo_name, workflows_dir).await? {
+        if !(file.ends_with(".yml") || file.ends_with(".yaml")) {
+            debug!("Skipping non YAML workflow file: {}", file);
+            continue;
+        }
+        if let Some(file_url) = check_file_exists(config, project_name, repo_name, &file).await? {
+            info!("Found GitHub workflow at: {}", file_url);
+            ci_files.push((file, file_url));
+        }
+    }
+
+    // GitLab CI
+    let gitlab_files = [".gitlab-ci.yml", ".gitlab-ci.yaml"];
+    for file in &gitlab_files {
+        if let Some(file_url) = check_file_exists(config, project_name, repo_name, file).await? {
+            info!("Found GitLab CI file at: {}", file_url);
+            ci_files.push((file.to_string(), file_url));
+        }
+    }
+
+    Ok(ci_files)
+}
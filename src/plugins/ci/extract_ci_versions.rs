@@ -0,0 +1,238 @@
+
+use serde_yaml::Value;
+use tracing::{debug, trace};
+
+use crate::types::MyError;
+
+// setup-* actions and the `with:` input holding the runtime version
+const SETUP_ACTIONS: [(&str, &str, &str); 5] = [
+    ("actions/setup-java", "java-version", "java"),
+    ("actions/setup-node", "node-version", "node"),
+    ("actions/setup-python", "python-version", "python"),
+    ("actions/setup-dotnet", "dotnet-version", "dotnet"),
+    ("actions/setup-go", "go-version", "go"),
+];
+
+/// Extract (tool, version) pairs from a GitHub Actions workflow or a GitLab CI file:
+/// setup-* runtime versions, matrix runtime versions, container image tags and pinned action versions.
+pub fn extract_ci_versions(content: &str) -> Result<Vec<(String, String)>, MyError> {
+    let doc: Value = serde_yaml::from_str(content)?;
+    let mut versions = Vec::new();
+
+    let Some(root) = doc.as_mapping() else {
+        return Ok(versions);
+    };
+
+    // GitLab: default image/services at the top level
+    collect_images(root.get("image"), root.get("services"), &mut versions);
+
+    // GitHub Actions jobs live under `jobs`, GitLab jobs are the other top-level mappings
+    let jobs: Vec<&Value> = match root.get("jobs").and_then(Value::as_mapping) {
+        Some(jobs) => jobs.values().collect(),
+        None => root.values().filter(|v| v.is_mapping()).collect(),
+    };
+
+    for job in jobs {
+        trace!("Analyzing CI job: {:?}", job);
+
+        // GitHub `container:` / GitLab `image:`, plus `services:` for both
+        collect_images(job.get("container").or_else(|| job.get("image")), job.get("services"), &mut versions);
+
+        // Matrix runtime versions, e.g. strategy.matrix.java: [11, 17]. Other keys (os, include...) are not versions
+        if let Some(matrix) = job.get("strategy").and_then(|s| s.get("matrix")).and_then(Value::as_mapping) {
+            for (key, values) in matrix {
+                let Some(key) = key.as_str() else { continue };
+                let tool = key.trim_end_matches("-version");
+                if !SETUP_ACTIONS.iter().any(|(_, _, runtime)| *runtime == tool) {
+                    continue;
+                }
+                for value in values.as_sequence().into_iter().flatten() {
+                    if let Some(version) = scalar_to_string(value) {
+                        debug!("Found matrix version {} for {}", version, tool);
+                        versions.push((tool.to_string(), version));
+                    }
+                }
+            }
+        }
+
+        // Steps: setup-* inputs and pinned action versions
+        for step in job.get("steps").and_then(Value::as_sequence).into_iter().flatten() {
+            let Some(uses) = step.get("uses").and_then(Value::as_str) else { continue };
+
+            if let Some(image) = uses.strip_prefix("docker://") {
+                push_image(image, &mut versions);
+                continue;
+            }
+            if uses.starts_with("./") {
+                continue; // Local action, nothing pinned
+            }
+
+            let Some((action, reference)) = uses.split_once('@') else { continue };
+
+            if let Some((_, input, tool)) = SETUP_ACTIONS.iter().find(|(name, _, _)| *name == action) {
+                if let Some(version) = step.get("with").and_then(|w| w.get(*input)).and_then(scalar_to_string) {
+                    debug!("Found {} version {} in {}", tool, version, action);
+                    versions.push((tool.to_string(), version));
+                }
+            }
+
+            // Commit SHA pins carry no version information
+            let is_sha = reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit());
+            if !is_sha {
+                let version = reference.trim_start_matches('v');
+                debug!("Found pinned action {}@{}", action, version);
+                versions.push((action.to_string(), version.to_string()));
+            }
+        }
+    }
+
+    Ok(versions)
+}
+
+fn collect_images(image: Option<&Value>, services: Option<&Value>, versions: &mut Vec<(String, String)>) {
+    if let Some(image) = image.and_then(image_name) {
+        push_image(image, versions);
+    }
+
+    // GitLab lists services, GitHub maps service names to definitions
+    let service_values: Vec<&Value> = match services {
+        Some(Value::Sequence(list)) => list.iter().collect(),
+        Some(Value::Mapping(map)) => map.values().collect(),
+        _ => vec![],
+    };
+    for service in service_values {
+        if let Some(image) = image_name(service) {
+            push_image(image, versions);
+        }
+    }
+}
+
+// An image is either a plain string or a mapping with `image:` (GitHub) / `name:` (GitLab)
+fn image_name(value: &Value) -> Option<&str> {
+    value
+        .as_str()
+        .or_else(|| value.get("image").and_then(Value::as_str))
+        .or_else(|| value.get("name").and_then(Value::as_str))
+}
+
+// `registry.example.com/library/maven:3.8-openjdk-11` gives ("maven", "3.8")
+fn push_image(image: &str, versions: &mut Vec<(String, String)>) {
+    if image.contains("${") {
+        return; // Templated image, resolved at runtime
+    }
+    let image = image.split('@').next().unwrap_or(image);
+    let name_and_tag = image.rsplit('/').next().unwrap_or(image);
+    let Some((name, tag)) = name_and_tag.split_once(':') else {
+        return; // No tag means `latest`
+    };
+
+    let version: String = tag
+        .trim_start_matches('v')
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let version = version.trim_end_matches('.');
+
+    if !version.is_empty() {
+        debug!("Found image {} with tag {}", name, tag);
+        versions.push((name.to_string(), version.to_string()));
+    }
+}
+
+// Runtime versions can be written as numbers (`java-version: 17`) or strings, skip expressions
+fn scalar_to_string(value: &Value) -> Option<String> {
+    let version = match value {
+        Value::String(s) => s.trim().to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => return None,
+    };
+    if version.is_empty() || version.starts_with("${{") {
+        None
+    } else {
+        Some(version)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(content: &str) -> Vec<(String, String)> {
+        extract_ci_versions(content).unwrap()
+    }
+
+    fn pairs(expected: &[(&str, &str)]) -> Vec<(String, String)> {
+        expected.iter().map(|(tool, version)| (tool.to_string(), version.to_string())).collect()
+    }
+
+    #[test]
+    fn setup_actions_inputs() {
+        let cases: [(&str, &[(&str, &str)]); 4] = [
+            (
+                "jobs:\n  build:\n    steps:\n      - uses: actions/setup-java@v4\n        with:\n          java-version: 17\n",
+                &[("java", "17"), ("actions/setup-java", "4")],
+            ),
+            (
+                "jobs:\n  build:\n    steps:\n      - uses: actions/setup-node@v3.8.1\n        with:\n          node-version: '20.x'\n",
+                &[("node", "20.x"), ("actions/setup-node", "3.8.1")],
+            ),
+            (
+                "jobs:\n  build:\n    steps:\n      - uses: actions/setup-python@v5\n        with:\n          python-version: ${{ matrix.python }}\n",
+                &[("actions/setup-python", "5")],
+            ),
+            ("jobs:\n  build:\n    steps:\n      - uses: actions/setup-go@v5\n", &[("actions/setup-go", "5")]),
+        ];
+        for (content, expected) in cases {
+            assert_eq!(versions(content), pairs(expected), "{}", content);
+        }
+    }
+
+    #[test]
+    fn matrix_runtime_versions() {
+        let content = "jobs:\n  test:\n    strategy:\n      matrix:\n        os: [ubuntu-latest, windows-latest]\n        java: [11, 17]\n        node-version: ['18', '20', '${{ github.event.inputs.node }}']\n        include:\n          - java: 21\n";
+        assert_eq!(versions(content), pairs(&[("java", "11"), ("java", "17"), ("node", "18"), ("node", "20")]));
+    }
+
+    #[test]
+    fn github_container_and_services() {
+        let content = "jobs:\n  build:\n    container:\n      image: maven:3.9.6-eclipse-temurin-17\n    services:\n      db:\n        image: postgres:15.4\n      cache: redis:7\n";
+        assert_eq!(versions(content), pairs(&[("maven", "3.9.6"), ("postgres", "15.4"), ("redis", "7")]));
+    }
+
+    #[test]
+    fn gitlab_images_and_services() {
+        let content = "image: node:18-alpine\nservices:\n  - name: postgres:14\n    alias: db\n  - docker:24.0-dind\nbuild:\n  image: registry.example.com/library/maven:3.8-openjdk-11\n  script: [mvn package]\n";
+        assert_eq!(versions(content), pairs(&[("node", "18"), ("postgres", "14"), ("docker", "24.0"), ("maven", "3.8")]));
+    }
+
+    #[test]
+    fn docker_steps_and_pins() {
+        let content = "jobs:\n  build:\n    steps:\n      - uses: docker://alpine:3.19\n      - uses: actions/checkout@8ade135a41bc03ea155e62e844d188df1ea18608\n      - uses: ./local-action\n      - uses: actions/cache@v4\n      - run: make\n";
+        assert_eq!(versions(content), pairs(&[("alpine", "3.19"), ("actions/cache", "4")]));
+    }
+
+    #[test]
+    fn image_names_and_tags() {
+        let cases = [
+            ("registry/library/maven:3.8-openjdk-11", Some(("maven", "3.8"))),
+            ("registry.example.com:5000/team/node:v20.1.0", Some(("node", "20.1.0"))),
+            ("python:3.12@sha256:0123abcd", Some(("python", "3.12"))),
+            ("eclipse-temurin:17.", Some(("eclipse-temurin", "17"))),
+            ("ubuntu", None),
+            ("node:lts", None),
+            ("${CI_REGISTRY_IMAGE}/builder:1.2", None),
+        ];
+        for (image, expected) in cases {
+            let mut found = vec![];
+            push_image(image, &mut found);
+            assert_eq!(found, pairs(expected.as_slice()), "{}", image);
+        }
+    }
+
+    #[test]
+    fn templated_and_invalid_files() {
+        assert!(versions("image: $CI_REGISTRY/${IMAGE}:1.0\n").is_empty());
+        assert!(versions("just a string").is_empty());
+        assert!(extract_ci_versions("jobs: [").is_err());
+    }
+}
@@ -0,0 +1,3 @@
+pub mod check_ci_files_exist;
+pub mod extract_ci_versions;
+pub mod analyze_ci;
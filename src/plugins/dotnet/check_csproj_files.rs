@@ -7,15 +7,15 @@ pub async fn check_csproj_files(
     config: &AppConfig,
     project_name: &str,
     repo_name: &str,
-) -> Result<bool, MyError> {
+) -> Result<Option<String>, MyError> {
     // List of .NET project files to check
     let csproj_files = ["*.csproj"]; // Adapt as needed
 
     for file in &csproj_files {
         if check_file_exists(config, project_name, repo_name, file).await?.is_some() {
-            return Ok(true);
+            return Ok(Some(file.to_string()));
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
@@ -1,7 +1,7 @@
 
 use crate::types::MyError;
 use crate::plugins::dotnet::check_csproj_files::check_csproj_files;
-use crate::models::{AppConfig, Analysis, DependencyVersion};
+use crate::models::{AppConfig, Analysis, DependencyVersion, Evidence};
 
 pub async fn check_dotnet(
     config: &AppConfig,
@@ -10,7 +10,7 @@ pub async fn check_dotnet(
     repository_name_str: &str,
     analyses: &mut Vec<Analysis>,
 ) -> Result<(), MyError> {
-    if check_csproj_files(config, project_name, repository_name).await? {
+    if let Some(file_path) = check_csproj_files(config, project_name, repository_name).await? {
         analyses.push(Analysis {
             repository_name: repository_name_str.to_string(),
             dependency_version: DependencyVersion {
@@ -18,6 +18,7 @@ pub async fn check_dotnet(
                 cycle: "exists".to_string(),
             },
             roadmap: None,
+            evidence: Evidence::new("dotnet", &file_path),
         });
     }
     Ok(())
@@ -3,7 +3,8 @@ use tracing::info;
 
 use crate::plugins::jenkins::check_jenkins_file_exists::check_jenkins_file_exists;
 use crate::plugins::jenkins::extract_version_from_groovy::extract_version_from_groovy;
-use crate::models::{AppConfig, Analysis, DependencyVersion};
+use crate::models::{AppConfig, Analysis, DependencyVersion, Evidence};
+use crate::utils::find_line_number::find_line_number;
 use crate::utils::run_get_request::run_get_request;
 use crate::plugins::jenkins::parse_groovy_properties::parse_groovy_properties;
 use crate::types::MyError;
@@ -18,29 +19,38 @@ pub async fn analyze_jenkins(
 ) -> Result<(), MyError> {
     info!("Start of Jenkins analysis");
 
-    if let Some(jenkins_file_url) = check_jenkins_file_exists(config, project_name, repository_name).await? {
+    if let Some((jenkins_file_path, jenkins_file_url)) = check_jenkins_file_exists(config, project_name, repository_name).await? {
         info!("Found Jenkins file at: {}", jenkins_file_url);
 
         // Fetch the Jenkins file content
-        let jenkins_file_content = run_get_request(config, &jenkins_file_url).await?.unwrap_or_else(|| String::new());
+        let jenkins_file_content = run_get_request(config, &jenkins_file_url).await?.unwrap_or_default();
 
         // Parse the Groovy properties
         let properties = parse_groovy_properties(&jenkins_file_content);
 
         for keyword in versions_keywords {
             // Try to extract the version from the parsed properties
-            if let Some(cycle) = extract_version_from_groovy(config, &properties, keyword) {
+            let versions = extract_version_from_groovy(config, &properties, keyword);
+            if versions.is_empty() {
+                info!("No version found for keyword '{}' in Jenkins file.", keyword);
+            }
+
+            for (cycle, property) in versions {
                 info!("Adding groovy props to analysis : {}/{}", keyword, cycle);
+                let evidence = Evidence {
+                    line: find_line_number(&jenkins_file_content, &property),
+                    matched_alias: Some(property),
+                    ..Evidence::new("jenkins", &jenkins_file_path)
+                };
                 analyses.push(Analysis {
                     repository_name: repository_name_str.to_string(),
                     dependency_version: DependencyVersion {
                         product: keyword.to_string(),
-                        cycle: cycle.to_string(),
+                        cycle,
                     },
                     roadmap: None,
+                    evidence,
                 });
-            } else {
-                info!("No version found for keyword '{}' in Jenkins file.", keyword);
             }
         }
     } else {
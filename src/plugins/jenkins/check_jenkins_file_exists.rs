@@ -8,7 +8,7 @@ pub async fn check_jenkins_file_exists(
     config: &AppConfig,
     project_name: &str,
     repo_name: &str,
-) -> Result<Option<String>, MyError> {
+) -> Result<Option<(String, String)>, MyError> {
     // List of Groovy files to check
     let groovy_files = [
         "Jenkinsfile.groovy", 
@@ -22,7 +22,7 @@ pub async fn check_jenkins_file_exists(
         info!("Checking : {}", file);
         if let Some(file_url) = check_file_exists(config, project_name, repo_name, file).await? {
             info!("content of jenkins file : {}", file_url);
-            return Ok(Some(file_url));
+            return Ok(Some((file.to_string(), file_url)));
         } else {
             info!("nothing returned from check jenkins file");
         }
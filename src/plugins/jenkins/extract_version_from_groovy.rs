@@ -7,7 +7,7 @@ pub fn extract_version_from_groovy(
     config: &AppConfig,
     properties: &HashMap<String, String>,
     keyword: &str,
-) -> Option<String> {
+) -> Vec<(String, String)> {  // (version, matched property name) for every alias found
     // Log the content of the properties
     info!("Jenkins properties:\n{:?}", properties);
 
@@ -22,8 +22,11 @@ pub fn extract_version_from_groovy(
     } else {
         info!("No equivalences found for keyword: {}", keyword);
     }
+    keywords_to_check.sort();
+    keywords_to_check.dedup();
 
-    // Iterate over each keyword and its equivalences, and try to find a version
+    // Iterate over each keyword and its equivalences, and keep every version found
+    let mut versions = Vec::new();
     for kw in &keywords_to_check {
         info!("Checking keyword: {}", kw);
 
@@ -31,13 +34,15 @@ pub fn extract_version_from_groovy(
         if let Some(cycle) = properties.get(kw) {
             let version_str = cycle.trim(); // Optionally trim whitespace
             debug!("Found version '{}' for keyword: {}", version_str, kw);
-            return Some(version_str.to_string());
+            versions.push((version_str.to_string(), kw.to_string()));
         } else {
             info!("No version found for keyword: {}", kw);
         }
     }
 
-    info!("No version found for any keyword related to: {}", keyword);
-    None
+    if versions.is_empty() {
+        info!("No version found for any keyword related to: {}", keyword);
+    }
+    versions
 }
 
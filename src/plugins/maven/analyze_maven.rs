@@ -2,9 +2,9 @@
 use tracing::warn;
 
 use crate::plugins::maven::process_pom::process_pom;
-use crate::models::{AppConfig, Analysis, DependencyVersion};
+use crate::models::{AppConfig, Analysis};
 use crate::plugins::maven::check_pom_xml_exists::check_pom_xml_exists;
-use crate::types::{CustomError, MyError};
+use crate::types::MyError;
 
 pub async fn analyze_maven(
     config: &AppConfig,
@@ -16,24 +16,21 @@ pub async fn analyze_maven(
 ) -> Result<(), MyError> {
     // Check for pom.xml in various possible locations
     match check_pom_xml_exists(config, project_name, repo_name).await {
-        Ok(Some(pom_url)) => {
+        Ok(Some((pom_path, pom_url))) => {
             // If a valid pom.xml is found, process it
-            match process_pom(config, project_name, repo_name, output_folder, &pom_url, versions_keywords).await {
+            match process_pom(config, project_name, repo_name, output_folder, &pom_path, &pom_url, versions_keywords).await {
                 Ok(versions_map) => {
-                    analyses.extend(versions_map.iter().map(|(product, value)| Analysis {
+                    analyses.extend(versions_map.into_iter().map(|(dependency_version, evidence)| Analysis {
                         repository_name: repo_name.to_string(),
-                        dependency_version: DependencyVersion {
-                            product: product.clone(),
-                            cycle: value.as_str().unwrap_or("").to_string(),
-                        },
+                        dependency_version,
                         roadmap: None,
+                        evidence,
                     }));
                     Ok(())
                 }
                 Err(e) => {
-                    let msg = format!("Failed to generate POM analysis for project '{}', repo '{}': {}", project_name, repo_name, e);
-                    warn!(msg);
-                    Err(CustomError::analysis_failed(&msg))
+                    warn!("Failed to generate POM analysis for project '{}', repo '{}': {}", project_name, repo_name, e);
+                    Err(e)
                 },
             }
         }
@@ -43,9 +40,8 @@ pub async fn analyze_maven(
             Ok(())
         }
         Err(e) => {
-            let msg = format!("Error while checking for pom.xml: {}", e);
-            warn!(msg);
-            Err(CustomError::analysis_failed(&msg))
+            warn!("Error while checking for pom.xml: {}", e);
+            Err(e)
         },
     }
 }
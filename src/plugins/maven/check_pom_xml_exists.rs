@@ -8,7 +8,7 @@ pub async fn check_pom_xml_exists(
     config: &AppConfig,
     project_name: &str,
     repo_name: &str,
-) -> Result<Option<String>, MyError> {
+) -> Result<Option<(String, String)>, MyError> {
     // List of pom.xml locations to check
     let pom_xml_paths = [
         "pom.xml",
@@ -25,14 +25,12 @@ pub async fn check_pom_xml_exists(
         match check_file_exists(config, project_name, repo_name, file).await {
             Ok(Some(file_url)) => {
                 info!("Found pom.xml at: {}", file_url);
-                return Ok(Some(file_url));  // Return the first valid pom.xml URL
+                return Ok(Some((file.to_string(), file_url)));  // Return the first valid pom.xml path and URL
             }
+            // A 404 from the platform
             Ok(None) => {
                 debug!("No pom.xml found at path: {}", file);
             }
-            Err(e) if e.to_string().contains("404") => {
-                debug!("404 Not Found at path: {}. Continuing to the next path.", file);
-            }
             Err(e) => {
                 info!("Error occurred while checking for pom.xml at path: {}. Error: {}", file, e);
                 return Err(e);
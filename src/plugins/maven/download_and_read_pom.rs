@@ -2,7 +2,7 @@
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
-use crate::types::MyError;
+use crate::types::{CustomError, MyError};
 use tracing::debug;
 
 use crate::utils::download_xml_file::download_xml_file;
@@ -17,19 +17,13 @@ pub async fn download_and_read_pom(
 ) -> Result<String, MyError> {
     let pom_file_path: PathBuf = Path::new(output_folder).join("pom.xml");
 
-    if pom_file_path.exists() && !config.force_git_pull {
-        debug!("POM file '{}' already exists, skipping download.", pom_file_path.display());
-    } else {
-        debug!("Downloading POM file from '{}'", pom_url);
-        let result = download_xml_file(config, pom_url, output_folder, "pom.xml").await;
-
-        if let Err(e) = result {
-            if e.to_string().contains("404 Not Found") {
-                return Err(format!("POM file not found at URL '{}'. Skipping repository '{}'.", pom_url, repo_name).into());
-            } else {
-                return Err(format!("Error while downloading POM file: {}", e).into());
-            }
-        }
+    // Always go through the HTTP cache, which knows whether the POM changed since the last run
+    debug!("Downloading POM file from '{}'", pom_url);
+    let downloaded = download_xml_file(config, pom_url, output_folder, "pom.xml").await?;
+
+    // Empty when the platform answered 404 or the POM could not be saved
+    if downloaded.is_empty() {
+        return Err(CustomError::not_found(format!("POM file at URL '{}' for repository '{}'", pom_url, repo_name)));
     }
 
     // Read the main POM file
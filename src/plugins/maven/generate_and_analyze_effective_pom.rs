@@ -2,11 +2,10 @@
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
-use serde_json::{Value, Map};
 use std::env;
 use tracing::debug;
 
-use crate::models::AppConfig;
+use crate::models::{AppConfig, DependencyVersion, Evidence};
 use crate::plugins::maven::utils::generate_maven_effective_pom::generate_maven_effective_pom;
 use crate::plugins::maven::utils::analyze_pom_content::analyze_pom_content;
 use crate::types::MyError;
@@ -17,7 +16,7 @@ pub fn generate_and_analyze_effective_pom(
     pom_file_path: &Path,
     repo_name: &str,
     output_folder: &str,
-) -> Result<Map<String, Value>, MyError> {
+) -> Result<Vec<(DependencyVersion, Evidence)>, MyError> {
     // Effective POM file path (relative)
     let effective_pom_file = Path::new(output_folder).join("effective_pom.xml");
 
@@ -29,8 +28,6 @@ pub fn generate_and_analyze_effective_pom(
     let absolute_effective_pom_file = current_dir.join(&effective_pom_file);
     debug!("Effective POM file absolute path: {}", absolute_effective_pom_file.display());
 
-    let mut pom_versions = Map::new();
-
     if !effective_pom_file.exists() || config.force_maven_effective {
         debug!(
             "2 - Effective POM file '{}' does not exist or force_effective is true, generating effective POM.",
@@ -56,10 +53,8 @@ pub fn generate_and_analyze_effective_pom(
         .map_err(|e| format!("Failed to read effective POM file '{}': {}", absolute_effective_pom_file.display(), e))?;
 
     // Analyze the POM content
-    let pom_analysis_result = analyze_pom_content(config, repo_name, &content, versions_keywords)?;
-    debug!("analyze_pom_content returns {}", pom_analysis_result);
-
-    pom_versions.extend(pom_analysis_result.get("versions").and_then(Value::as_object).unwrap_or(&Map::new()).clone());
+    let pom_versions = analyze_pom_content(config, repo_name, &content, versions_keywords)?;
+    debug!("analyze_pom_content returns {:?}", pom_versions);
 
     Ok(pom_versions)
 }
@@ -1,11 +1,11 @@
 
 use std::path::Path;
-use serde_json::{Value, Map};
 use crate::types::MyError;
 use tracing::{info, debug};
 
 use crate::utils::download_xml_file::download_xml_file;
-use crate::models::AppConfig;
+use crate::models::{AppConfig, DependencyVersion, Evidence};
+use crate::utils::find_line_number::find_line_number;
 use crate::plugins::maven::utils::parse_pom_for_modules::parse_pom_for_modules;
 use crate::plugins::maven::generate_and_analyze_effective_pom::generate_and_analyze_effective_pom;
 use crate::plugins::maven::download_and_read_pom::download_and_read_pom;
@@ -16,9 +16,10 @@ pub async fn process_pom(
     project_name: &str,
     repo_name: &str,
     output_folder: &str,
+    pom_path: &str,
     pom_url: &str,
     versions_keywords: &[&str],
-) -> Result<Map<String, Value>, MyError> {
+) -> Result<Vec<(DependencyVersion, Evidence)>, MyError> {
     // Step 1: Download and read the POM
     let main_pom_content = download_and_read_pom(config, output_folder, pom_url, repo_name).await?;
 
@@ -32,7 +33,7 @@ pub async fn process_pom(
             std::fs::create_dir_all(&module_target_folder)
                 .map_err(|e| format!("Failed to create directory for module '{}': {}", module, e))?;
 
-            let module_pom_url = config.url_config.raw_file_url(project_name, repo_name, &format!("{}/pom.xml", &module));
+            let module_pom_url = config.url_config.raw_file_url(project_name, repo_name, &format!("{}/pom.xml", &module), config.branch.as_deref());
             debug!("Submodule downloading pom file from {}", module_pom_url);
 
             download_xml_file(
@@ -50,6 +51,21 @@ pub async fn process_pom(
     let pom_file_path = Path::new(output_folder).join("pom.xml");
     debug!("output_folder {}", output_folder);
 
-    generate_and_analyze_effective_pom(config, versions_keywords, &pom_file_path, repo_name, output_folder)
+    let mut versions = generate_and_analyze_effective_pom(config, versions_keywords, &pom_file_path, repo_name, output_folder)?;
+
+    // Step 4: Point each finding back to the main POM. Versions inherited from a parent POM keep no line.
+    for (_, evidence) in versions.iter_mut() {
+        evidence.file_path = Some(pom_path.to_string());
+        evidence.line = match (&evidence.artifact, &evidence.matched_alias) {
+            (Some(artifact), _) => {
+                let artifact_id = artifact.rsplit(':').next().unwrap_or(artifact);
+                find_line_number(&main_pom_content, &format!("<artifactId>{}</artifactId>", artifact_id))
+            }
+            (None, Some(property)) => find_line_number(&main_pom_content, &format!("<{}>", property)),
+            (None, None) => None,
+        };
+    }
+
+    Ok(versions)
 }
 
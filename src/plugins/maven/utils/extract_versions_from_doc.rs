@@ -5,12 +5,39 @@ use crate::types::MyError;
 use regex::Regex;
 use tracing::{trace, debug};
 
+use crate::models::{DependencyVersion, Evidence};
+
+fn pom_evidence(artifact: Option<String>, matched_alias: &str) -> Evidence {
+    Evidence {
+        plugin: "maven".to_string(),
+        artifact,
+        matched_alias: Some(matched_alias.to_string()),
+        ..Default::default()
+    }
+}
+
+// Keep every occurrence, but only once per (product, version, artifact)
+fn push_match(versions: &mut Vec<(DependencyVersion, Evidence)>, product: &str, cycle: &str, evidence: Evidence) {
+    let already_found = versions.iter().any(|(found, found_evidence)| {
+        found.product == product && found.cycle == cycle && found_evidence.artifact == evidence.artifact
+    });
+    if !already_found {
+        versions.push((
+            DependencyVersion {
+                product: product.to_string(),
+                cycle: cycle.to_string(),
+            },
+            evidence,
+        ));
+    }
+}
+
 pub fn extract_versions_from_doc(
     doc: &Document,
     equivalences: &HashMap<String, Vec<String>>,
     version_keywords: &[&str],
     version_regex: &Regex,
-    versions: &mut HashMap<String, String>,
+    versions: &mut Vec<(DependencyVersion, Evidence)>,
 ) -> Result<(), MyError> {
     for keyword in version_keywords {
         debug!("Analyzing keyword: '{}'", keyword);
@@ -29,6 +56,7 @@ pub fn extract_versions_from_doc(
                 let version_text = version_node.text().unwrap_or("");
 
                 trace!("Found dependency: groupId='{}', artifactId='{}', version='{}'", group_id_text, artifact_id_text, version_text);
+                let coordinates = format!("{}:{}", group_id_text, artifact_id_text);
 
                 // Check equivalences for the keyword
                 for (equiv_keyword, references) in equivalences {
@@ -40,11 +68,11 @@ pub fn extract_versions_from_doc(
                                 // Use regex to extract version if applicable
                                 if let Some(caps) = version_regex.captures(version_text) {
                                     let extracted_version = caps.get(1).map(|m| m.as_str()).unwrap_or("unknown");
-                                    versions.insert(keyword.to_string(), extracted_version.to_string());
+                                    push_match(versions, keyword, extracted_version, pom_evidence(Some(coordinates.clone()), reference));
                                     debug!("Extracted version '{}' for keyword '{}'", extracted_version, keyword);
                                 } else {
                                     // Store the raw version text if no regex applies
-                                    versions.insert(keyword.to_string(), version_text.to_string());
+                                    push_match(versions, keyword, version_text, pom_evidence(Some(coordinates.clone()), reference));
                                     debug!("Stored version '{}' for keyword '{}'", version_text, keyword);
                                 }
                             }
@@ -55,7 +83,7 @@ pub fn extract_versions_from_doc(
                 // Direct match on artifactId
                 if artifact_id_text == *keyword {
                     let cleaned_version = version_text.trim_start_matches('~').trim_start_matches('^');
-                    versions.insert(keyword.to_string(), cleaned_version.to_string());
+                    push_match(versions, keyword, cleaned_version, pom_evidence(Some(coordinates.clone()), keyword));
                     debug!("Directly matched and found version '{}' for artifactId '{}'", cleaned_version, artifact_id_text);
                 }
             }
@@ -71,7 +99,7 @@ pub fn extract_versions_from_doc(
             if tag_name == version_key {
                 if let Some(cycle) = node.text() {
                     let cleaned_version = cycle.trim_start_matches('~').trim_start_matches('^');
-                    versions.insert(keyword.to_string(), cleaned_version.to_string());
+                    push_match(versions, keyword, cleaned_version, pom_evidence(None, &version_key));
                     debug!("Found version '{}' for keyword '{}'", cleaned_version, keyword);
                 }
             }
@@ -84,10 +112,10 @@ pub fn extract_versions_from_doc(
         if let Some(properties_node) = doc.descendants().find(|node| node.tag_name().name() == "properties") {
             trace!("Analyzing <properties> section for '{}'", version_key);
 
-            for prop in properties_node.descendants().filter(|node| node.tag_name().name() == &version_key) {
+            for prop in properties_node.descendants().filter(|node| node.tag_name().name() == version_key) {
                 if let Some(version_text) = prop.text() {
                     let cleaned_version = version_text.trim_start_matches('~').trim_start_matches('^');
-                    versions.insert(keyword.to_string(), cleaned_version.to_string());
+                    push_match(versions, keyword, cleaned_version, pom_evidence(None, &version_key));
                     debug!("Found version '{}' for keyword '{}' in properties", cleaned_version, keyword);
                 }
             }
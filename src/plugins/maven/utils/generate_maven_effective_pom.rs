@@ -6,9 +6,9 @@ use tracing::{debug, trace, error};
 use std::io::ErrorKind;
 
 pub fn generate_maven_effective_pom(pom_file: &str, repo_name: &str) -> Result<String, MyError> {
-    let effective_pom_file = format!("effective_pom.xml");
+    let effective_pom_file = "effective_pom.xml".to_string();
     let output_option = format!("-Doutput={}", effective_pom_file);
-    let pom_file = format!("{}", &pom_file);
+    let pom_file = pom_file.to_string();
 
     // Trace the working directory and POM details
     debug!("Preparing to run Maven effective-pom for file '{}', outputting to '{}'", &pom_file, &output_option);
@@ -54,7 +54,7 @@ pub fn generate_maven_effective_pom(pom_file: &str, repo_name: &str) -> Result<S
         let stderr = String::from_utf8_lossy(&output.stderr);
         error!("Maven command failed for repo {} with status code: {}", repo_name, status_code);
         error!("stderr: {}", stderr);
-        Err(Box::new(std::io::Error::new(ErrorKind::Other, "Maven command failed")))
+        Err(Box::new(std::io::Error::other("Maven command failed")))
     }
 }
 
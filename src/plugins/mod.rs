@@ -4,4 +4,5 @@ pub mod docker;
 pub mod dotnet;
 pub mod php;
 pub mod jenkins;
+pub mod ci;
 pub mod analyze_one_repo;
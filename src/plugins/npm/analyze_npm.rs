@@ -1,10 +1,9 @@
 
 use crate::types::MyError;
-use serde_json::Value;
 use tracing::info;
 
 use crate::plugins::npm::analyze_package_json_content::analyze_package_json_content;
-use crate::models::{AppConfig, Analysis, DependencyVersion};
+use crate::models::{AppConfig, Analysis};
 
 pub async fn analyze_npm(
     config: &AppConfig,
@@ -16,41 +15,28 @@ pub async fn analyze_npm(
     info!("Analyzing package.json for repository: {}", repository_name);
 
     // Analyze the package.json content
-    let package_json_analysis_result = analyze_package_json_content(config, project_name, repository_name, versions_keywords).await?;
-
-    info!("package_json_analysis_result : {:?}", package_json_analysis_result);
-
-    // Extract versions from the result
-    let versions = package_json_analysis_result.get("versions").and_then(Value::as_object);
-
-    if let Some(versions) = versions {
-        // Map the versions into Analysis objects
-        for (product, version_value) in versions {
-            let version_str = version_value.as_str().unwrap_or_default();
-
-            // Construct DependencyVersion
-            let dependency_version = DependencyVersion {
-                product: product.to_string(),
-                cycle: version_str.to_string(),
-            };
-
-            // Create the Analysis object
-            let analysis = Analysis {
-                repository_name: repository_name.to_string(),
-                dependency_version,
-                roadmap: None, // Set this to None unless there's logic for it
-            };
-
-            info!(
-                "Created analysis for product: {}, cycle: {}",
-                product, version_str
-            );
-            analyses.push(analysis);
-        }
-    } else {
+    let versions = analyze_package_json_content(config, project_name, repository_name, versions_keywords).await?;
+
+    info!("package_json_analysis_result : {:?}", versions);
+
+    if versions.is_empty() {
         info!("No versions found in the package.json result.");
     }
 
+    // Map the versions into Analysis objects
+    for (dependency_version, evidence) in versions {
+        info!(
+            "Created analysis for product: {}, cycle: {}",
+            dependency_version.product, dependency_version.cycle
+        );
+
+        analyses.push(Analysis {
+            repository_name: repository_name.to_string(),
+            dependency_version,
+            roadmap: None,
+            evidence,
+        });
+    }
+
     Ok(())
 }
-
@@ -1,11 +1,11 @@
 
-use std::collections::HashMap;
 use crate::types::MyError;
 use serde_json::{json, Value};
 use tracing::{debug, info};
 
-use crate::models::AppConfig;
-use crate::utils::run_json_get_query::run_json_get_query;
+use crate::models::{AppConfig, DependencyVersion, Evidence};
+use crate::utils::run_get_request::run_get_request;
+use crate::utils::find_line_number::find_line_number;
 use crate::plugins::npm::check_package_json_exists::check_package_json_exists;
 
 // Function to get the version from dependencies or devDependencies
@@ -26,25 +26,28 @@ pub async fn analyze_package_json_content(
     project_name: &str,
     repo_name: &str,
     dependencies_list: &[&str],  // List of product names
-) -> Result<Value, MyError> {
-    // Check if package.json exists and get the file URL
-    let file_url = match check_package_json_exists(config, project_name, repo_name).await? {
-        Some(url) => url,
+) -> Result<Vec<(DependencyVersion, Evidence)>, MyError> {
+    // Check if package.json exists and get the file path and URL
+    let (file_path, file_url) = match check_package_json_exists(config, project_name, repo_name).await? {
+        Some(found) => found,
         None => {
             info!("No package.json found in the repository. Skipping analysis.");
-            return Ok(json!({
-                "repository": repo_name,
-                "versions": {}  // Return an empty object or desired default value
-            }));
+            return Ok(Vec::new());
         }
     };
 
     info!("Fetching package.json from URL: {}", file_url);
 
-    // Fetch package.json content using the file URL
-    let package_json: Value = run_json_get_query(config, &file_url).await?;
+    // Fetch the raw package.json content, kept to locate each dependency line
+    let content = run_get_request(config, &file_url).await?.unwrap_or_default();
+    let package_json: Value = if content.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Error parsing package.json '{}': {}", file_path, e))?
+    };
 
-    let mut versions = HashMap::new();
+    let mut versions = Vec::new();
 
     // There's no "lines" array, so directly work with the JSON object
     let package_json_value = package_json;
@@ -68,6 +71,8 @@ pub async fn analyze_package_json_content(
             // Extend with equivalences if they exist
             keywords_to_check.extend(equivalences.clone());
         }
+        keywords_to_check.sort();
+        keywords_to_check.dedup();
 
         // Log the keywords that are being checked
         info!(
@@ -75,7 +80,7 @@ pub async fn analyze_package_json_content(
             product, keywords_to_check
         );
 
-        // Iterate over each keyword (product + equivalences)
+        // Iterate over each keyword (product + equivalences), keeping every match
         for kw in &keywords_to_check {
             // Log the search for each keyword in dependencies and devDependencies
             debug!("Looking for keyword '{}' in 'dependencies'", kw);
@@ -94,8 +99,22 @@ pub async fn analyze_package_json_content(
                     "Found version '{}' for keyword '{}' in 'dependencies' or 'devDependencies'",
                     cycle, kw
                 );
-                versions.insert(product.to_string(), cycle);  // Use the original product name for insertion
-                break; // Stop searching once a version is found
+                let evidence = Evidence {
+                    plugin: "npm".to_string(),
+                    file_path: Some(file_path.clone()),
+                    line: find_line_number(&content, &format!("\"{}\"", kw)),
+                    artifact: Some(kw.to_string()),
+                    matched_alias: Some(kw.to_string()),
+                    url: None,
+                };
+                // Keep every alias found, e.g. both `angular` and `@angular/core`
+                versions.push((
+                    DependencyVersion {
+                        product: product.to_string(),  // Use the original product name
+                        cycle,
+                    },
+                    evidence,
+                ));
             } else {
                 info!(
                     "Keyword '{}' not found in 'dependencies' or 'devDependencies'",
@@ -105,14 +124,8 @@ pub async fn analyze_package_json_content(
         }
     }
 
-    // Build the JSON output
-    let result = json!({
-        "repository": repo_name,
-        "versions": versions,
-    });
-
-    info!("Result package json: {}", result);
+    info!("Result package json for {}: {:?}", repo_name, versions);
 
-    Ok(result)
+    Ok(versions)
 }
 
@@ -8,7 +8,7 @@ pub async fn check_package_json_exists(
     config: &AppConfig,
     project_name: &str,
     repo_name: &str,
-) -> Result<Option<String>, MyError> {
+) -> Result<Option<(String, String)>, MyError> {
     // List of package.json locations to check
     let package_json_paths = [
         "package.json",
@@ -28,15 +28,12 @@ pub async fn check_package_json_exists(
         match check_file_exists(config, project_name, repo_name, file).await {
             Ok(Some(file_url)) => {
                 info!("Found package.json at: {}", file_url);
-                return Ok(Some(file_url));  // Return the first valid package.json URL
+                return Ok(Some((file.to_string(), file_url)));  // Return the first valid package.json path and URL
             }
+            // A 404 from the platform
             Ok(None) => {
                 debug!("No package.json found at path: {}", file);
             }
-            Err(e) if e.to_string().contains("404") => {
-                // Log the 404 error and continue to the next path
-                debug!("404 Not Found at path: {}. Continuing to the next path.", file);
-            }
             Err(e) => {
                 // For other errors, return them
                 info!("Error occurred while checking for package.json at path: {}. Error: {}", file, e);
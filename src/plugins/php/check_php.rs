@@ -2,7 +2,7 @@
 use crate::types::MyError;
 
 use crate::plugins::php::check_php_files::check_php_files;
-use crate::models::{AppConfig, Analysis, DependencyVersion};
+use crate::models::{AppConfig, Analysis, DependencyVersion, Evidence};
 
 pub async fn check_php(
     config: &AppConfig,
@@ -11,7 +11,7 @@ pub async fn check_php(
     repository_name_str: &str,
     analyses: &mut Vec<Analysis>,
 ) -> Result<(), MyError> {
-    if check_php_files(config, project_name, repository_name).await? {
+    if let Some(file_path) = check_php_files(config, project_name, repository_name).await? {
         analyses.push(Analysis {
             repository_name: repository_name_str.to_string(),
             dependency_version: DependencyVersion {
@@ -19,6 +19,7 @@ pub async fn check_php(
                 cycle: "exists".to_string(),
             },
             roadmap: None,
+            evidence: Evidence::new("php", &file_path),
         });
     }
     Ok(())
@@ -7,7 +7,7 @@ pub async fn check_php_files(
     config: &AppConfig,
     project_name: &str,
     repo_name: &str,
-) -> Result<bool, MyError> {
+) -> Result<Option<String>, MyError> {
 
     // List of PHP-related files to check
     let php_files = [
@@ -19,9 +19,9 @@ pub async fn check_php_files(
 
     for file in &php_files {
         if check_file_exists(config, project_name, repo_name, file).await?.is_some() {
-            return Ok(true);
+            return Ok(Some(file.to_string()));
         }
     }
 
-    Ok(false)
+    Ok(None)
 }
@@ -0,0 +1,43 @@
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde_json::json;
+
+use crate::types::{error_chain, CustomError, MyError};
+
+// Error categories mapped to the closest HTTP statuses, the same ones as the gRPC codes
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        ApiError { status, message: message.into() }
+    }
+
+    // Errors shared by the requests waiting for the same job
+    pub fn from_error(err: &MyError) -> Self {
+        let status = match CustomError::root(err.as_ref()) {
+            Some(CustomError::NotFound(_)) => StatusCode::NOT_FOUND,
+            Some(CustomError::InvalidInput(_)) => StatusCode::BAD_REQUEST,
+            Some(CustomError::Auth(_)) => StatusCode::FORBIDDEN,
+            Some(CustomError::CircuitOpen { .. }) => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, error_chain(err.as_ref()))
+    }
+}
+
+impl From<MyError> for ApiError {
+    fn from(err: MyError) -> Self {
+        ApiError::from_error(&err)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(json!({ "error": self.message }))).into_response()
+    }
+}
@@ -0,0 +1,309 @@
+use std::sync::Arc;
+use axum::extract::{Path, Query, State};
+use axum::Extension;
+use axum::http::StatusCode;
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::info;
+
+use crate::auth::Caller;
+use crate::cli::ScanTarget;
+use crate::jobs::{Job, JobState, ScanEvent, ScanResults};
+use crate::jobs::job_queue::{JobPriority, JobQueue, ScanRequest};
+use crate::models::{AppConfig, DashboardRow, PolicyReport, Roadmap};
+use crate::rest_server::api_error::ApiError;
+use crate::services::build_dashboard::build_dashboard;
+use crate::services::evaluate_policy::evaluate_policy;
+use crate::services::get_roadmap::get_roadmap;
+use crate::services::list_roadmaps::list_roadmaps;
+use crate::services::search_roadmaps::search_roadmaps;
+use crate::types::{CustomError, MyError};
+
+// Branch and plugins of an analysis, `plugins` is a comma separated list
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ScanOptions {
+    branch: String,
+    plugins: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StartScanBody {
+    #[serde(flatten)]
+    target: ScanTarget,
+    #[serde(default)]
+    branch: String,
+    #[serde(default)]
+    plugins: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct EventsQuery {
+    from: usize,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ProductsQuery {
+    filter: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnalyzeResponse {
+    message: String,
+    projects: Vec<ProjectResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectResult {
+    project: String,
+    repositories: Vec<Value>, // Reports as written to the output folder
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectDebt {
+    project: String,
+    #[serde(flatten)]
+    debt: PolicyReport,
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobStatus {
+    job_id: String,
+    target: String,
+    state: JobState,
+    events: usize, // The next event has this sequence
+}
+
+#[derive(Debug, Serialize)]
+pub struct JobEvents {
+    job_id: String,
+    state: JobState,
+    events: Vec<SequencedEvent>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SequencedEvent {
+    sequence: usize,
+    #[serde(flatten)]
+    event: ScanEvent,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProductsResponse {
+    products: Vec<String>,
+}
+
+impl From<&Job> for JobStatus {
+    fn from(job: &Job) -> Self {
+        JobStatus { job_id: job.id.clone(), target: job.target.clone(), state: job.state(), events: job.event_count() }
+    }
+}
+
+// Projects sorted by name, like the gRPC responses
+fn analyze_response(message: String, results: &ScanResults) -> AnalyzeResponse {
+    let mut projects: Vec<ProjectResult> = results
+        .iter()
+        .map(|(project, reports)| ProjectResult { project: project.clone(), repositories: reports.clone() })
+        .collect();
+    projects.sort_by(|a, b| a.project.cmp(&b.project));
+    AnalyzeResponse { message, projects }
+}
+
+fn db(config: &AppConfig) -> Result<&sled::Db, MyError> {
+    config.db.as_ref().ok_or_else(|| CustomError::database_error("Database is not initialized"))
+}
+
+// The jobs of other callers and tenants are unknown
+fn job(queue: &JobQueue, job_id: &str, caller: &Caller) -> Result<Arc<Job>, ApiError> {
+    queue
+        .get(job_id)
+        .filter(|job| caller.may_watch(job))
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("No job '{}'", job_id)))
+}
+
+fn required(name: String, field: &str) -> Result<String, ApiError> {
+    if name.is_empty() {
+        return Err(CustomError::invalid_input(format!("{} is required", field)).into());
+    }
+    Ok(name)
+}
+
+// Queued before the scheduled scans, answered when the job is over
+async fn analyze(
+    queue: &JobQueue,
+    target: ScanTarget,
+    options: ScanOptions,
+    caller: Caller,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    caller.config(&queue.config())?;
+    let plugins = options.plugins.split(',').map(str::trim).filter(|p| !p.is_empty()).map(String::from).collect();
+    let request = ScanRequest { target, branch: options.branch, plugins, projects: caller.projects, tenant: caller.tenant };
+    info!(caller = %caller.name, target = %request.label(), "Scan requested");
+    let job = queue.enqueue(request, JobPriority::OnDemand, &caller.name)?;
+
+    match job.results().await {
+        Some(Ok(results)) => {
+            info!(target = %job.target, "Analysis successful");
+            Ok(Json(analyze_response(format!("{} analyzed successfully", job.target), &results)))
+        }
+        Some(Err(e)) => Err(ApiError::from_error(&e)),
+        None => Err(ApiError::new(StatusCode::CONFLICT, format!("Job {} was cancelled", job.id))),
+    }
+}
+
+pub async fn analyze_repository(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(name): Path<String>,
+    Query(options): Query<ScanOptions>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    analyze(&queue, ScanTarget::Repo { name }, options, caller).await
+}
+
+pub async fn analyze_project(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(name): Path<String>,
+    Query(options): Query<ScanOptions>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    analyze(&queue, ScanTarget::Project { name }, options, caller).await
+}
+
+pub async fn analyze_all(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Query(options): Query<ScanOptions>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    analyze(&queue, ScanTarget::All, options, caller).await
+}
+
+// Queued in the background, followed with the job endpoints
+pub async fn start_scan(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Json(body): Json<StartScanBody>,
+) -> Result<(StatusCode, Json<JobStatus>), ApiError> {
+    let target = match body.target {
+        ScanTarget::Repo { name } => ScanTarget::Repo { name: required(name, "name")? },
+        ScanTarget::Project { name } => ScanTarget::Project { name: required(name, "name")? },
+        ScanTarget::All => ScanTarget::All,
+    };
+    caller.config(&queue.config())?;
+    let request = ScanRequest { target, branch: body.branch, plugins: body.plugins, projects: caller.projects, tenant: caller.tenant };
+    let job = queue.enqueue(request, JobPriority::OnDemand, &caller.name)?;
+    info!(job_id = %job.id, target = %job.target, "Scan queued");
+    Ok((StatusCode::ACCEPTED, Json(JobStatus::from(job.as_ref()))))
+}
+
+pub async fn get_job_status(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobStatus>, ApiError> {
+    Ok(Json(JobStatus::from(job(&queue, &job_id, &caller)?.as_ref())))
+}
+
+// History of the job from a sequence, polled until `job_finished`
+pub async fn get_job_events(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(job_id): Path<String>,
+    Query(query): Query<EventsQuery>,
+) -> Result<Json<JobEvents>, ApiError> {
+    let job = job(&queue, &job_id, &caller)?;
+    let (history, _) = job.subscribe();
+    let events = history
+        .into_iter()
+        .enumerate()
+        .skip(query.from)
+        .map(|(sequence, event)| SequencedEvent { sequence, event })
+        .collect();
+    Ok(Json(JobEvents { job_id: job.id.clone(), state: job.state(), events }))
+}
+
+pub async fn cancel_job(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobStatus>, ApiError> {
+    let job = job(&queue, &job_id, &caller)?;
+    let job = queue
+        .cancel(&job.id)?
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("No job '{}'", job_id)))?;
+    Ok(Json(JobStatus::from(job.as_ref())))
+}
+
+pub async fn get_latest_results(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    let config = queue.config();
+    let results = caller.load_results(&config, None)?;
+    Ok(Json(analyze_response("Latest results of all projects".to_string(), &results)))
+}
+
+pub async fn get_project_results(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(project): Path<String>,
+) -> Result<Json<AnalyzeResponse>, ApiError> {
+    let config = queue.config();
+    let results = caller.load_results(&config, Some(&project))?;
+    Ok(Json(analyze_response(format!("Latest results of {}", project), &results)))
+}
+
+// Debt counts of every project in the last results, checked against `policy`
+pub async fn list_projects(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<Vec<ProjectDebt>>, ApiError> {
+    let config = queue.config();
+    let results = caller.load_results(&config, None)?;
+    let config = caller.config(&config)?;
+    let mut projects: Vec<ProjectDebt> = results
+        .into_iter()
+        .map(|(project, reports)| {
+            let debt = evaluate_policy(config, &ScanResults::from([(project.clone(), reports)]));
+            ProjectDebt { project, debt }
+        })
+        .collect();
+    projects.sort_by(|a, b| a.project.cmp(&b.project));
+    Ok(Json(projects))
+}
+
+// Every KPI of the last results with its roadmap domain and chapter
+pub async fn get_dashboard(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<Vec<DashboardRow>>, ApiError> {
+    let config = queue.config();
+    let results = caller.load_results(&config, None)?;
+    let config = caller.config(&config)?;
+    Ok(Json(build_dashboard(db(config)?, &results)?))
+}
+
+pub async fn list_products(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Query(query): Query<ProductsQuery>,
+) -> Result<Json<ProductsResponse>, ApiError> {
+    let config = queue.config();
+    let config = caller.config(&config)?;
+    let db = db(config)?;
+    let roadmaps = if query.filter.is_empty() { list_roadmaps(db)? } else { search_roadmaps(config, db, &query.filter)? };
+    Ok(Json(ProductsResponse { products: roadmaps.into_iter().map(|roadmap| roadmap.product).collect() }))
+}
+
+pub async fn get_product_roadmap(
+    State(queue): State<JobQueue>,
+    Extension(caller): Extension<Caller>,
+    Path(product): Path<String>,
+) -> Result<Json<Roadmap>, ApiError> {
+    let config = queue.config();
+    get_roadmap(db(caller.config(&config)?)?, &product)?
+        .map(Json)
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("No roadmap for product '{}'", product)))
+}
@@ -0,0 +1,66 @@
+mod api_error;
+mod handlers;
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{Html, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use tracing::info;
+
+use crate::auth::authenticate::authenticate;
+use crate::jobs::job_queue::JobQueue;
+use crate::types::MyError;
+use api_error::ApiError;
+use handlers::*;
+
+// OpenAPI document of the routes below
+pub const OPENAPI: &str = include_str!("../../openapi.json");
+
+// Dashboard of the last results, reading `/api/dashboard`
+const DASHBOARD: &str = include_str!("dashboard.html");
+
+// Bearer token of the `authorization` header, checked against the current configuration, and tenant of the `tenant` header
+async fn check_token(State(queue): State<JobQueue>, mut request: Request, next: Next) -> Result<Response, ApiError> {
+    let headers = request.headers();
+    let value = |key: &str| headers.get(key).and_then(|value| value.to_str().ok());
+    let caller = authenticate(&queue.config().api_tokens, value(header::AUTHORIZATION.as_str()), value("tenant"))
+        .map_err(|e| ApiError::new(StatusCode::UNAUTHORIZED, e.to_string()))?;
+    request.extensions_mut().insert(caller);
+    Ok(next.run(request).await)
+}
+
+// Same operations as the gRPC service, on the same job queue. The dashboard page and the OpenAPI document are public
+fn router(queue: JobQueue) -> Router {
+    let api = Router::new()
+        .route("/api/repositories/:name/analyze", post(analyze_repository))
+        .route("/api/projects/:name/analyze", post(analyze_project))
+        .route("/api/analyze", post(analyze_all))
+        .route("/api/scans", post(start_scan))
+        .route("/api/jobs/:id", get(get_job_status).delete(cancel_job))
+        .route("/api/jobs/:id/events", get(get_job_events))
+        .route("/api/results", get(get_latest_results))
+        .route("/api/projects", get(list_projects))
+        .route("/api/projects/:name/results", get(get_project_results))
+        .route("/api/dashboard", get(get_dashboard))
+        .route("/api/products", get(list_products))
+        .route("/api/roadmaps/:product", get(get_product_roadmap))
+        .route_layer(middleware::from_fn_with_state(queue.clone(), check_token));
+
+    Router::new()
+        .route("/", get(|| async { Html(DASHBOARD) }))
+        .route("/api/openapi.json", get(|| async { ([(header::CONTENT_TYPE, "application/json")], OPENAPI) }))
+        .merge(api)
+        .with_state(queue)
+}
+
+pub async fn start_rest_server(address: &str, queue: JobQueue) -> Result<(), MyError> {
+    let listener = tokio::net::TcpListener::bind(address).await?;
+    info!(address = %address, "Starting REST server");
+
+    axum::serve(listener, router(queue)).await?;
+
+    info!("REST server has been shut down");
+    Ok(())
+}
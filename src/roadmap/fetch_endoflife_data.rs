@@ -1,21 +1,23 @@
 
-// use reqwest::blocking::Client;
-use reqwest::Client;
 use serde_json::Value;
-use std::error::Error;
 use tracing::{info, trace, debug};
 
+use crate::models::AppConfig;
+use crate::utils::send_with_retry::send_with_retry;
+use crate::types::{CustomError, MyError};
+
 // Fetch data from the End of Life API for a specific product
-pub async fn fetch_endoflife_data(product: &str) -> Result<Vec<Value>, Box<dyn Error>> {
+pub async fn fetch_endoflife_data(config: &AppConfig, product: &str) -> Result<Vec<Value>, MyError> {
     debug!("Fetching end-of-life data for product: {}", product);
 
     let url = format!("https://endoflife.date/api/{}.json", product);
-    let client = Client::new();
+    let request = config.client.get(&url).header("Accept", "application/json");
 
-    match client.get(&url).header("Accept", "application/json").send().await {
+    match send_with_retry(config, request, &url).await {
         Ok(response) => {
             trace!("Received response for product: {} content : {:?}", product, response);
-            let json_data: Vec<Value> = response.json().await?;
+            let json_data: Vec<Value> = response.json().await
+                .map_err(|e| CustomError::parse(format!("end-of-life data for {}", product), e))?;
             trace!("Parsed JSON data for product: {} content : {:?}", product, json_data);
             Ok(json_data)
         }
@@ -2,3 +2,4 @@ pub mod persist_to_sled;
 pub mod process_yaml_files;
 pub mod read_yaml;
 pub mod fetch_endoflife_data;
+pub mod reload_roadmaps;
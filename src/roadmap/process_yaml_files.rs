@@ -39,7 +39,7 @@ pub async fn process_yaml_files(config: &AppConfig, dir_path: &str) -> Result<()
         if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("yml") {
             if let Some(path_str) = path.to_str() {
                 // Read and process the YAML file
-                let roadmap_yaml = read_yaml(&config, path_str).await?;
+                let roadmap_yaml = read_yaml(config, path_str).await?;
                 persist_to_sled(db, &roadmap_yaml)?;
                 info!("Processed file: {}", path_str);
             }
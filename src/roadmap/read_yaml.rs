@@ -28,7 +28,7 @@ pub async fn read_yaml(config: &AppConfig, file_path: &str) -> Result<Roadmaps,
 
         for product_name in product_names_to_check {
             trace!("Fetching End of Life data for product: {}", product_name);
-            match fetch_endoflife_data(&product_name).await {
+            match fetch_endoflife_data(config, &product_name).await {
                 Ok(eol_data) => {
                     trace!("Fetched end-of-life data for product: {}", product_name);
 
@@ -36,8 +36,8 @@ pub async fn read_yaml(config: &AppConfig, file_path: &str) -> Result<Roadmaps,
                     let eol_roadmap_entries: Vec<RoadmapEntry> = eol_data.iter().map(|entry| {
                         RoadmapEntry {
                             cycle: entry["cycle"].as_str().unwrap_or_default().to_string(),
-                            release_date: entry["release_date"].as_str().map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()).flatten(),
-                            eol: entry["eol"].as_str().map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()).flatten(),
+                            release_date: entry["release_date"].as_str().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+                            eol: entry["eol"].as_str().and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
                             extended_end_date: None, // API does not provide extended end date
                             comment: Some("Source: End-of-life API".to_string()),
                             source_name: Some("end-of-life".to_string()),
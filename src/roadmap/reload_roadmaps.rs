@@ -0,0 +1,46 @@
+use std::collections::HashSet;
+use std::fs;
+use tracing::info;
+
+use crate::roadmap::read_yaml::read_yaml;
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Replace the roadmap of the DB with the content of the roadmap folder.
+// Files are read (and enriched) first, then the products are replaced in one atomic batch:
+// analyses running during the reload see either the old roadmap or the new one.
+pub async fn reload_roadmaps(config: &AppConfig) -> Result<(), MyError> {
+    let db = config.db.as_ref().ok_or_else(|| CustomError::database_error("Database is not initialized"))?;
+
+    let mut roadmaps = vec![];
+    for entry in fs::read_dir(&config.roadmap_folder)? {
+        let path = entry?.path();
+        if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("yml") {
+            if let Some(path_str) = path.to_str() {
+                roadmaps.push(read_yaml(config, path_str).await?);
+            }
+        }
+    }
+
+    // Same keys as `persist_to_sled`, a product of a later file replaces the one of an earlier file
+    let mut batch = sled::Batch::default();
+    let mut products = HashSet::new();
+    for roadmap in roadmaps.iter().flat_map(|roadmap_yaml| &roadmap_yaml.roadmap_list) {
+        let key = roadmap.product.to_lowercase().into_bytes();
+        batch.insert(key.clone(), serde_json::to_vec(roadmap)?);
+        products.insert(key);
+    }
+
+    // Only the default tree holds the roadmap, the HTTP cache and scan states are kept
+    for key in db.iter().keys() {
+        let key = key?;
+        if !products.contains(key.as_ref()) {
+            batch.remove(key);
+        }
+    }
+    db.apply_batch(batch)?;
+    db.flush_async().await?;
+
+    info!("Roadmap reloaded from {} ({} files)", config.roadmap_folder, roadmaps.len());
+    Ok(())
+}
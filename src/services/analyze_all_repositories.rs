@@ -1,67 +1,34 @@
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use futures::stream::{self, StreamExt, TryStreamExt};
 use serde_json::{Value, json};
 
+use crate::boot::config_for_source::config_for_source;
 use crate::models::AppConfig;
 use crate::services::get_projects::get_projects;
+use crate::services::map_repository_project::map_repository_project;
 use crate::services::run_analysis::run_analysis;
 use crate::fetch_repositories::fetch_repositories;
 use crate::utils::append_json_to_file::append_json_to_file;
 use crate::types::MyError;
 
-pub async fn analyze_all_repositories(config: &AppConfig) -> Result<(), MyError> {
-    // Fetch projects
-    let projects = get_projects(config).await?;
-
+// Results are returned by project, as written to all_projects.json
+pub async fn analyze_all_repositories(config: &AppConfig) -> Result<HashMap<String, Vec<Value>>, MyError> {
+    let http_start = config.http_stats.snapshot();
     // Initialize a HashMap to accumulate analysis results for all repositories grouped by project
     let mut all_analysis_results: HashMap<String, Vec<Value>> = HashMap::new();
 
-    for project in projects {
-        // Determine the platform
-        let platform = &config.platform; // Assuming platform is stored in the config
-
-        // Extract project name based on platform
-        let project_name = match platform.as_str() {
-            "github" => project["full_name"]
-                .as_str()
-                .ok_or("Failed to get project name")?, // Use full_name for GitHub
-            "bitbucket" => project["key"]
-                .as_str()
-                .ok_or("Failed to get project name")?, // Use key for Bitbucket
-            _ => return Err("Unsupported platform".into()),
-        };
-
-        // Initialize a vector to accumulate analysis results for the current project
-        let mut project_analysis_results = Vec::new();
-
-        if platform == "github" {
-            // For GitHub, run analysis directly without fetching repositories
-            if let Some(json_data) = run_analysis(config, project_name, project_name).await? {
-                // Accumulate the analysis result for the current project
-                project_analysis_results.push(json_data.clone());
-            }
-        } else {
-            // For Bitbucket, fetch repositories and run analysis
-            let all_repos = fetch_repositories(config, project_name).await?;
-            for repo in all_repos {
-                let repo_name = repo["name"].as_str().ok_or("Missing repo name")?;
-
-                // Run the analysis and check if valid JSON is returned
-                if let Some(json_data) = run_analysis(config, project_name, repo_name).await? {
-                    // Accumulate the analysis result for the current project
-                    project_analysis_results.push(json_data.clone());
-                }
-            }
-        }
-
-        // After processing all repositories of the current project, append the results to a project-specific JSON file
-        if !project_analysis_results.is_empty() {
-            let json_project_result = json!(project_analysis_results);
-            append_json_to_file(config, project_name, &json_project_result)?;  // Save per-project JSON
+    // Every source is scanned into the same consolidated result
+    for source in &config.sources {
+        tracing::info!("Scanning source '{}' ({} at {})", source.name, source.platform, source.base_url);
+        let source_config = config_for_source(config, source)?;
+        analyze_source(&source_config, &mut all_analysis_results).await?;
+    }
 
-            // Add the project and its repositories to the `all_analysis_results`
-            all_analysis_results.insert(project_name.to_string(), project_analysis_results);
-        }
+    // Append the results of every project to a project-specific JSON file
+    for (project_name, project_analysis_results) in &all_analysis_results {
+        let json_project_result = json!(project_analysis_results);
+        append_json_to_file(config, project_name, &json_project_result)?;  // Save per-project JSON
     }
 
     // After processing all projects and repositories, append the accumulated results to the `all_projects.json` file
@@ -70,6 +37,59 @@ pub async fn analyze_all_repositories(config: &AppConfig) -> Result<(), MyError>
         append_json_to_file(config, "all_projects", &json_all_projects_result)?;  // Save all projects JSON with nested structure
     }
 
-    Ok(())
+    config.http_stats.log_summary(&http_start);
+
+    Ok(all_analysis_results)
 }
 
+
+async fn analyze_source(
+    config: &AppConfig,
+    all_analysis_results: &mut HashMap<String, Vec<Value>>,
+) -> Result<(), MyError> {
+    // Fetch projects
+    let projects = get_projects(config).await?;
+
+    // A repository can be listed by several scopes (organisation and team)
+    let mut analysed_repos: HashSet<String> = HashSet::new();
+    let mut repositories = vec![];
+
+    for project in projects {
+        let scope = project["key"]
+            .as_str()
+            .ok_or("Failed to get project name")?;
+
+        let all_repos = fetch_repositories(config, scope).await?;
+        for repo in all_repos {
+            let repo_name = config.url_config.repo_name(&repo).ok_or("Missing repo name")?;
+            let owner = config.url_config.repo_owner(scope, &repo);
+
+            // Topic filter and organisation/topic to project mapping
+            let Some(project_name) = map_repository_project(config, scope, &repo) else {
+                continue;
+            };
+            if !analysed_repos.insert(format!("{}/{}", owner, repo_name)) {
+                continue;
+            }
+            repositories.push((project_name, owner, repo_name));
+        }
+    }
+
+    // `concurrency` analyses at a time, results keep the listing order
+    let results: Vec<(String, Option<Value>)> = stream::iter(repositories)
+        .map(|(project_name, owner, repo_name)| async move {
+            run_analysis(config, &owner, &repo_name).await.map(|json_data| (project_name, json_data))
+        })
+        .buffered(config.concurrency)
+        .try_collect()
+        .await?;
+
+    // Accumulate the analysis result for the repository project
+    for (project_name, json_data) in results {
+        if let Some(json_data) = json_data {
+            all_analysis_results.entry(project_name).or_default().push(json_data);
+        }
+    }
+
+    Ok(())
+}
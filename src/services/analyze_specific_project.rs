@@ -1,45 +1,73 @@
 use std::collections::HashMap;
+use std::io::IsTerminal;
 use dialoguer::Input;
-use serde_json::json;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde_json::{json, Value};
+use crate::boot::config_for_source::config_for_source;
 use crate::fetch_repositories::fetch_repositories;
+use crate::services::map_repository_project::map_repository_project;
 use crate::services::run_analysis::run_analysis;
 use crate::models::AppConfig;
 use crate::utils::append_json_to_file::append_json_to_file;
-use crate::types::MyError;
+use crate::types::{CustomError, MyError};
 
 pub async fn analyze_specific_project(
     config: &AppConfig,
-) -> Result<(), MyError> {
+    project_name_arg: Option<&str>,
+) -> Result<HashMap<String, Vec<Value>>, MyError> {
+    let http_start = config.http_stats.snapshot();
     // Prompt for the project name
-    let project_name: String = Input::new()
-        .with_prompt("Enter the project name (e.g., PTEP):")
-        .interact()?;
+    let project_name: String = match project_name_arg {
+        Some(name) => name.to_string(),
+        None if !std::io::stdin().is_terminal() => {
+            return Err(CustomError::invalid_input("Project name required, no terminal to ask for it"));
+        }
+        None => Input::new()
+            .with_prompt("Enter the project name (e.g., PTEP, or a GitHub organisation):")
+            .interact()?,
+    };
 
     // Initialize a HashMap to store the project's analysis results
     let mut all_analysis_results: HashMap<String, Vec<serde_json::Value>> = HashMap::new();
 
-    // Fetch all repositories for the given project
-    let all_repos = fetch_repositories(config, &project_name).await?;
-
     // Initialize a vector to accumulate analysis results for all repositories
     let mut project_analysis_results = Vec::new();
 
-    // Iterate over all repositories
-    for repo in all_repos {
-        if let Some(repo_obj) = repo.as_object() {
-            let repo_name = repo_obj.get("name")
-                .and_then(|v| v.as_str())
-                .ok_or("Missing repo name")?;
-
-            // Run the analysis for the repository
-            if let Some(json_data) = run_analysis(config, &project_name, repo_name).await? {
-                // Accumulate the analysis result
-                project_analysis_results.push(json_data);
+    // The project is looked up in every source
+    for source in &config.sources {
+        let config = &config_for_source(config, source)?;
+
+        // Fetch all repositories for the given project
+        let all_repos = fetch_repositories(config, &project_name).await?;
+
+        // Iterate over all repositories
+        let mut repositories = vec![];
+        for repo in all_repos {
+            if repo.is_object() {
+                let repo_name = config.url_config.repo_name(&repo)
+                    .ok_or_else(|| CustomError::not_found("Missing repo name"))?;
+
+                // GitHub topic filter
+                if map_repository_project(config, &project_name, &repo).is_none() {
+                    continue;
+                }
+                let owner = config.url_config.repo_owner(&project_name, &repo);
+                repositories.push((owner, repo_name));
+            } else {
+                tracing::error!("Invalid repository format for project '{}'", project_name);
+                return Err(CustomError::project_error(format!("Invalid repository format for project '{}'", project_name)));
             }
-        } else {
-            tracing::error!("Invalid repository format for project '{}'", project_name);
-            return Err("Invalid repository format".into());
         }
+
+        // Run the analysis of `concurrency` repositories at a time
+        let results: Vec<Option<Value>> = stream::iter(repositories)
+            .map(|(owner, repo_name)| async move { run_analysis(config, &owner, &repo_name).await })
+            .buffered(config.concurrency)
+            .try_collect()
+            .await?;
+
+        // Accumulate the analysis result
+        project_analysis_results.extend(results.into_iter().flatten());
     }
 
     // After processing all repositories, create the nested structure and save
@@ -54,5 +82,7 @@ pub async fn analyze_specific_project(
         append_json_to_file(config, &project_name, &json_result)?;
     }
 
-    Ok(())
+    config.http_stats.log_summary(&http_start);
+
+    Ok(all_analysis_results)
 }
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use chrono::Utc;
+use serde_json::Value;
+
+use crate::kpi::utils::sanitize_version::sanitize_version;
+use crate::kpi::utils::version_matches::version_matches;
+use crate::models::{DashboardRow, Roadmap, RepositoryReport};
+use crate::services::get_roadmap::get_roadmap;
+use crate::types::{CustomError, MyError};
+
+// Every KPI of scan results, classified with the domain and chapter of its roadmap.
+// The KPI only keeps the days left before the end of life, the days past it come from the roadmap entry.
+pub fn build_dashboard(db: &sled::Db, results: &HashMap<String, Vec<Value>>) -> Result<Vec<DashboardRow>, MyError> {
+    let today = Utc::now().date_naive();
+    let mut roadmaps: HashMap<String, Option<Roadmap>> = HashMap::new();
+    let mut rows = Vec::new();
+
+    for (project, reports) in results {
+        for report in reports {
+            let report: RepositoryReport = serde_json::from_value(report.clone())
+                .map_err(|e| CustomError::parse(format!("report of project {}", project), e))?;
+
+            for kpi in report.debt {
+                let roadmap = match roadmaps.get(&kpi.product) {
+                    Some(roadmap) => roadmap,
+                    None => {
+                        let roadmap = get_roadmap(db, &kpi.product)?;
+                        roadmaps.entry(kpi.product.clone()).or_insert(roadmap)
+                    }
+                };
+                let cycle = sanitize_version(&kpi.cycle);
+                let eol = roadmap
+                    .as_ref()
+                    .and_then(|roadmap| roadmap.entries.iter().find(|entry| version_matches(&cycle, &entry.cycle)))
+                    .and_then(|entry| entry.eol.or(entry.extended_end_date));
+                let days_past_eol = eol.map(|eol| today.signed_duration_since(eol).num_days()).filter(|days| *days > 0);
+
+                rows.push(DashboardRow {
+                    project: project.clone(),
+                    repository: report.application.clone(),
+                    repository_status: report.status,
+                    product: kpi.product,
+                    cycle: kpi.cycle,
+                    status: kpi.status,
+                    reason: kpi.reason,
+                    domain: roadmap.as_ref().and_then(|roadmap| roadmap.domain.clone()),
+                    chapter: roadmap.as_ref().and_then(|roadmap| roadmap.chapter.clone()),
+                    eol,
+                    days_past_eol,
+                    evidence: kpi.evidence,
+                });
+            }
+        }
+    }
+
+    rows.sort_by(|a, b| (&a.project, &a.repository, &a.product).cmp(&(&b.project, &b.repository, &b.product)));
+    Ok(rows)
+}
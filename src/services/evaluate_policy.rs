@@ -0,0 +1,39 @@
+use std::collections::HashMap;
+use serde_json::Value;
+
+use crate::models::{AppConfig, PolicyReport};
+
+// Count the findings of scan results (project -> repository reports) and check them against `policy`
+pub fn evaluate_policy(config: &AppConfig, results: &HashMap<String, Vec<Value>>) -> PolicyReport {
+    let mut report = PolicyReport::default();
+
+    for repository in results.values().flatten() {
+        report.repositories += 1;
+        if repository["status"] == "Failed" {
+            report.failed += 1;
+        }
+        for kpi in repository["debt"].as_array().into_iter().flatten() {
+            match kpi["status"].as_str() {
+                Some("Outdated") => report.outdated += 1,
+                Some("NonCompliant") => report.non_compliant += 1,
+                _ => {}
+            }
+        }
+    }
+
+    let thresholds = [
+        ("outdated", report.outdated, config.policy.max_outdated),
+        ("non compliant", report.non_compliant, config.policy.max_non_compliant),
+        ("failed", report.failed, config.policy.max_failed),
+    ];
+    for (name, count, max) in thresholds {
+        if let Some(max) = max.filter(|max| count > *max) {
+            report.breaches.push(format!("{} {} (max {})", count, name, max));
+        }
+    }
+
+    if !report.breaches.is_empty() {
+        tracing::warn!("Policy breached: {}", report.breaches.join(", "));
+    }
+    report
+}
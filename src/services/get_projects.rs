@@ -1,50 +1,61 @@
-use serde_json::Value;
+use serde_json::{json, Value};
 use tracing::info;
 
 use crate::models::AppConfig;
-use crate::utils::run_json_get_query::run_json_get_query;
+use crate::utils::fetch_all_pages::fetch_all_pages;
 use crate::types::MyError;
 
 pub async fn get_projects(config: &AppConfig) -> Result<Vec<Value>, MyError> {
+    let mut projects = list_projects(config).await?;
+
+    // Source filter on project keys, organisations, teams or workspaces
+    if let Some(source) = config.source.as_ref().filter(|source| !source.projects.is_empty()) {
+        projects.retain(|project| {
+            project["key"]
+                .as_str()
+                .map(|key| source.projects.iter().any(|p| p.eq_ignore_ascii_case(key)))
+                .unwrap_or(false)
+        });
+    }
+
+    Ok(projects)
+}
+
+async fn list_projects(config: &AppConfig) -> Result<Vec<Value>, MyError> {
     let url_config = &*config.url_config; // Dereference the Box
 
+    // GitHub projects are the configured organisations and teams, or the user repositories
+    if config.platform == "github" {
+        let scopes: Vec<&String> = config.github.orgs.iter().chain(&config.github.teams).collect();
+        let projects = if scopes.is_empty() {
+            vec![json!({ "key": "" })]
+        } else {
+            scopes.into_iter().map(|scope| json!({ "key": scope })).collect()
+        };
+        return Ok(projects);
+    }
+
+    // Bitbucket Cloud projects are the configured workspaces, or every workspace of the credentials
+    if config.platform == "bitbucket-cloud" && !config.bitbucket_cloud.workspaces.is_empty() {
+        let projects = config.bitbucket_cloud.workspaces.iter().map(|workspace| json!({ "key": workspace })).collect();
+        return Ok(projects);
+    }
+
     // Get the base URL for the API to fetch the list of projects
     let projects_url = url_config.projects_url(); // Fetch the list of projects
     info!("Fetching projects from URL: {}", projects_url);
 
-    // Call run_json_get_query to perform the GET request
-    let response_json = run_json_get_query(config, &projects_url).await?;
-
-    // Check if the response contains an error
-    // if response_json.get("error").is_some() {
-    //     return Err(format!(
-    //         "Failed to fetch projects: Error in response from '{}'",
-    //         projects_url
-    //     )
-    //     .into());
-    // }
-    //
-    // // Determine the platform and parse projects accordingly
-    let platform = &config.platform; // Assuming you have a field `platform` in your config
-    let projects = match platform.as_str() {
-        "bitbucket" => {
-            // For Bitbucket, extract projects from the `values` key
-            response_json["values"]
-                .as_array()
-                .ok_or("Failed to parse Bitbucket projects list")?
-                .to_vec()
-        }
-        "github" => {
-            // For GitHub, extract directly from the response array
-            response_json
-                .as_array()
-                .ok_or("Failed to parse GitHub projects list")?
-                .to_vec()
-        }
-        _ => {
-            return Err("Unsupported platform".into());
-        }
-    };
+    // Follow every page, large organisations have more projects than a page holds
+    let mut projects = fetch_all_pages(config, &projects_url).await?;
+
+    // { "workspace": { "slug": "acme", ... }, "permission": "member" }
+    if config.platform == "bitbucket-cloud" {
+        projects = projects
+            .iter()
+            .filter_map(|membership| membership["workspace"]["slug"].as_str())
+            .map(|workspace| json!({ "key": workspace }))
+            .collect();
+    }
 
     Ok(projects)
 }
@@ -0,0 +1,14 @@
+use serde_json;
+use sled;
+use crate::models::Roadmap;
+use crate::types::MyError;
+
+// Every roadmap of the sled DB, sorted by product (keys are the lowercase products)
+pub fn list_roadmaps(db: &sled::Db) -> Result<Vec<Roadmap>, MyError> {
+    let mut roadmaps = vec![];
+    for entry in db.iter() {
+        let (_, serialized_product) = entry?;
+        roadmaps.push(serde_json::from_slice(&serialized_product)?);
+    }
+    Ok(roadmaps)
+}
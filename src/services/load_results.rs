@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::fs;
+use serde_json::Value;
+
+use crate::models::AppConfig;
+use crate::types::{CustomError, MyError};
+
+// Results written by the last scan, all_projects.json or the file of one project.
+// The project name comes from the callers of the servers, it must name a file of the output folder.
+pub fn load_results(config: &AppConfig, project: Option<&str>) -> Result<HashMap<String, Vec<Value>>, MyError> {
+    if let Some(project) = project.filter(|project| project.contains(['/', '\\']) || project.contains("..")) {
+        return Err(CustomError::invalid_input(format!("Invalid project name '{}'", project)));
+    }
+    let name = project.map(str::to_lowercase).unwrap_or_else(|| "all_projects".to_string());
+    let file_path = format!("{}/{}.json", config.output_folder, name);
+
+    let content = fs::read_to_string(&file_path)
+        .map_err(|e| CustomError::not_found(format!("{}: {} (run a scan first)", file_path, e)))?;
+    let json: Value = serde_json::from_str(&content).map_err(|e| CustomError::parse(file_path.clone(), e))?;
+
+    match json {
+        // Project file written by `scan all`: the reports of its repositories
+        Value::Array(reports) => Ok(HashMap::from([(name, reports)])),
+        // Project -> reports
+        Value::Object(projects) => projects
+            .into_iter()
+            .map(|(project, reports)| match reports {
+                Value::Array(reports) => Ok((project, reports)),
+                _ => Err(CustomError::invalid_input(format!("{}: '{}' is not a list of reports", file_path, project))),
+            })
+            .collect(),
+        _ => Err(CustomError::invalid_input(format!("{}: unexpected content", file_path))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Output folder of a test in the temporary folder, with the files of a scan
+    fn output_folder(name: &str) -> String {
+        let folder = std::env::temp_dir().join(format!("xdebt-results-{}-{}", std::process::id(), name));
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("all_projects.json"), r#"{"PTEP": [{"repository": "a"}], "SES": []}"#).unwrap();
+        fs::write(folder.join("ptep.json"), r#"[{"repository": "a"}, {"repository": "b"}]"#).unwrap();
+        folder.display().to_string()
+    }
+
+    #[test]
+    fn names_outside_the_output_folder_are_rejected() {
+        let config = AppConfig { output_folder: output_folder("rejected"), ..AppConfig::default() };
+        for project in ["../secret", "..", "a/b", "/etc/passwd", "a\\b", "ptep..", "..\\x"] {
+            let e = load_results(&config, Some(project)).unwrap_err();
+            assert_eq!(CustomError::kind(e.as_ref()), "invalid_input", "{}", project);
+        }
+        fs::remove_dir_all(&config.output_folder).unwrap();
+    }
+
+    #[test]
+    fn all_projects_by_default() {
+        let config = AppConfig { output_folder: output_folder("all"), ..AppConfig::default() };
+        let results = load_results(&config, None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results["PTEP"].len(), 1);
+        assert!(results["SES"].is_empty());
+
+        let results = load_results(&config, Some("PTEP")).unwrap();
+        assert_eq!(results["ptep"].len(), 2);
+        assert_eq!(CustomError::kind(load_results(&config, Some("other")).unwrap_err().as_ref()), "not_found");
+        fs::remove_dir_all(&config.output_folder).unwrap();
+    }
+}
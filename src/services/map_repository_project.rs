@@ -0,0 +1,50 @@
+
+use serde_json::Value;
+
+use crate::models::AppConfig;
+
+/// xdebt project under which a repository listed in `scope` is reported,
+/// `None` when the GitHub topic filter excludes the repository.
+pub fn map_repository_project(config: &AppConfig, scope: &str, repo: &Value) -> Option<String> {
+    match config.platform.as_str() {
+        "github" => map_github_project(config, scope, repo),
+        "bitbucket-cloud" => Some(map_bitbucket_cloud_project(config, scope, repo)),
+        // Bitbucket Server projects are xdebt projects
+        _ => Some(scope.to_string()),
+    }
+}
+
+// A mapped workspace project wins over the workspace
+fn map_bitbucket_cloud_project(config: &AppConfig, workspace: &str, repo: &Value) -> String {
+    let projects = &config.bitbucket_cloud.projects;
+    repo["project"]["key"]
+        .as_str()
+        .and_then(|key| projects.get(key))
+        .or_else(|| projects.get(workspace))
+        .cloned()
+        .unwrap_or_else(|| workspace.to_string())
+}
+
+fn map_github_project(config: &AppConfig, scope: &str, repo: &Value) -> Option<String> {
+    let scopes = &config.github;
+    let topics: Vec<&str> = repo["topics"]
+        .as_array()
+        .map(|topics| topics.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    if !scopes.topics.is_empty() && !topics.iter().any(|topic| scopes.topics.iter().any(|t| t == topic)) {
+        return None;
+    }
+
+    // A mapped topic wins over the scope, then the organisation owning the repository
+    let owner = config.url_config.repo_owner(scope, repo);
+    let project = topics
+        .iter()
+        .find_map(|topic| scopes.projects.get(*topic))
+        .or_else(|| scopes.projects.get(scope))
+        .or_else(|| scopes.projects.get(&owner))
+        .cloned()
+        .unwrap_or(owner);
+
+    Some(project)
+}
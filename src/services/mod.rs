@@ -6,3 +6,10 @@ pub mod search_dependency_in_sled;
 pub mod get_projects;
 pub mod get_roadmap;
 pub mod get_distinct_dependencies;
+pub mod scan_state;
+pub mod map_repository_project;
+pub mod evaluate_policy;
+pub mod list_roadmaps;
+pub mod load_results;
+pub mod search_roadmaps;
+pub mod build_dashboard;
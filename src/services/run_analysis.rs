@@ -1,14 +1,23 @@
 
+use std::sync::Mutex;
 use std::time::{Instant, Duration};
-use serde_json::{Value, json};
+use serde_json::Value;
 use crate::plugins::analyze_one_repo::analyze_one_repo;
-use crate::models::AppConfig;
+use crate::models::{AppConfig, AnalysisError, AnalysisStatus, RepositoryReport};
 use crate::kpi::compute_kpi::compute_kpi;
+use crate::kpi::aggregate_kpi::aggregate_kpi;
 use crate::models::KPIResult;
 use crate::utils::remove_null_values::remove_null_values;
-use crate::types::MyError;
+use crate::utils::get_head_commit::get_head_commit;
+use crate::utils::enrich_versions_with_roadmap::enrich_versions_with_roadmap;
+use crate::services::scan_state::{load_scan_state, save_scan_state, scan_fingerprint, state_key, ScanState};
+use crate::utils::http_cache::forget_previous_commit;
+use crate::jobs::ScanEvent;
+use crate::jobs::emit_event::emit_event;
+use crate::types::{error_chain, MyError};
 
-static mut TOTAL_DURATION: Duration = Duration::new(0, 0);  // Static variable to hold total duration
+// Total time spent in analyses, repositories can be analysed concurrently
+static TOTAL_DURATION: Mutex<Duration> = Mutex::new(Duration::new(0, 0));
 
 pub async fn run_analysis(
     config: &AppConfig,
@@ -20,109 +29,174 @@ pub async fn run_analysis(
         return Ok(None);  // Return None for skipped repos
     }
 
+    // Repositories excluded by the source filters
+    let source_name = config.source.as_ref().map(|source| source.name.clone()).unwrap_or_default();
+    if let Some(source) = &config.source {
+        for pattern in &source.exclude_patterns {
+            if pattern.is_match(repo_name) {
+                tracing::debug!("Repo {} excluded by '{}' in source {}", repo_name, pattern, source.name);
+                return Ok(None);
+            }
+        }
+    }
+
     // Start timing the analysis
     let start_time = Instant::now();
+    emit_event(config, || ScanEvent::RepositoryStarted {
+        project: project_name.to_string(),
+        repository: repo_name.to_string(),
+    });
+
+    // The head commit pins the cached content and tells whether the repository changed
+    let mut repo_config = config.clone();
+    if (config.http.cache_enabled || config.incremental) && !config.force_git_pull {
+        match get_head_commit(config, project_name, repo_name).await {
+            Ok(commit) => {
+                if let (Some(commit), true) = (&commit, config.http.cache_enabled) {
+                    let repository = state_key(config, project_name, repo_name);
+                    if let Err(e) = forget_previous_commit(config, &repository, commit) {
+                        tracing::warn!("Cached files of the previous commit of {} not removed: {}", repository, e);
+                    }
+                }
+                repo_config.head_commit = commit;
+            }
+            Err(e) => tracing::debug!("Could not get head commit of {}/{}: {}", project_name, repo_name, e),
+        }
+    }
+    let config = &repo_config;
+
+    // Incremental mode: reuse the findings of the last scan when no commit was pushed since,
+    // and the plugins, roadmap products and equivalences are the same
+    let fingerprint = match (&config.head_commit, config.incremental) {
+        (Some(_), true) => Some(scan_fingerprint(config)?),
+        _ => None,
+    };
+    let previous_scan = match (&config.head_commit, &fingerprint) {
+        (Some(head_commit), Some(fingerprint)) => load_scan_state(config, project_name, repo_name)?
+            .filter(|state| &state.head_commit == head_commit && &state.fingerprint == fingerprint),
+        _ => None,
+    };
+    let reused = previous_scan.is_some();
 
     // Perform the analysis
-    match analyze_one_repo(config, project_name, repo_name).await {
-        Ok(mut analysis_results) => {
+    let outcome = match previous_scan {
+        Some(state) => {
+            tracing::info!(
+                "Repo {}/{} unchanged since {} (commit {}), reusing its findings",
+                project_name, repo_name, state.scanned_at, state.head_commit
+            );
+            // Roadmaps may have changed, enrich again
+            let db = config.db.as_ref().expect("Db should be initialized");
+            enrich_versions_with_roadmap(db, state.analyses).await.map(|analyses| (analyses, vec![]))
+        }
+        None => analyze_one_repo(config, project_name, repo_name).await,
+    };
+
+    let report = match outcome {
+        Ok((mut analysis_results, errors)) => {
             tracing::info!("Project: {}, Repo: {}", project_name, repo_name);
             tracing::debug!("Analysis result: {}", serde_json::to_string_pretty(&analysis_results)?);
 
             // Compute KPIs based on the analysis results
             let kpi_results: Vec<KPIResult> = analysis_results
                 .iter_mut()
-                .filter_map(|analysis| compute_kpi(&config, analysis)) // Filter out None values
+                .filter_map(|analysis| compute_kpi(config, analysis)) // Filter out None values
                 .collect();
 
+            // Only a complete fresh scan with the configured plugins is worth reusing
+            if let (Some(head_commit), Some(fingerprint), false, true, false) =
+                (&config.head_commit, &fingerprint, reused, errors.is_empty(), config.plugins_selected)
+            {
+                let state = ScanState {
+                    head_commit: head_commit.clone(),
+                    fingerprint: fingerprint.clone(),
+                    scanned_at: chrono::Utc::now(),
+                    analyses: analysis_results.iter().cloned().map(|mut a| { a.roadmap = None; a }).collect(),
+                };
+                save_scan_state(config, project_name, repo_name, &state)?;
+            }
+
             // Log KPIs
             for kpi in &kpi_results {
                 tracing::info!("KPI Result: {}", serde_json::to_string_pretty(kpi)?);
+                emit_event(config, || ScanEvent::KpiComputed {
+                    repository: repo_name.to_string(),
+                    product: kpi.product.clone(),
+                    cycle: kpi.cycle.clone(),
+                    status: kpi.status,
+                });
             }
 
-            // Only proceed if kpi_results is not empty
-            if !kpi_results.is_empty() {
-                // Use a Vec to enforce field order
-                let json_data = vec![
-                    ("application", json!(repo_name)),  // Add the repo name first
-                    ("debt", json!(kpi_results))            // Then the debt (KPI results)
-                ];
-
-                // Convert the Vec to a Value::Object by converting tuples to key-value pairs
-                let mut flattened_json = serde_json::Map::new();
-                for (key, value) in json_data {
-                    flattened_json.insert(key.to_string(), value);
-                }
-
-                let mut final_json = Value::Object(flattened_json);
-                remove_null_values(&mut final_json);  // Remove null entries
-
-                tracing::debug!("Json result: {}", &final_json);
-
-                // Log the duration of this analysis
-                let duration = start_time.elapsed();
-                tracing::info!(
-                    "Analysis completed for project: {}, repo: {} in {:?}",
-                    project_name,
-                    repo_name,
-                    duration
-                );
-
-                // Accumulate total duration
-                unsafe {
-                    TOTAL_DURATION += duration;
-                }
-
-                // Log the total accumulated time
-                tracing::info!("Total time so far: {:?}", unsafe { TOTAL_DURATION });
-
-                // Return the final JSON data
-                return Ok(Some(final_json));
+            // A plugin failure makes the result incomplete, whatever was found
+            let status = if errors.is_empty() {
+                AnalysisStatus::Complete
             } else {
-                tracing::info!("No KPIs to record for project: {}, repo: {}", project_name, repo_name);
-
-                // Log the duration of this analysis
-                let duration = start_time.elapsed();
-                tracing::info!(
-                    "Analysis completed for project: {}, repo: {} in {:?} with no KPIs",
-                    project_name,
-                    repo_name,
-                    duration
-                );
-
-                // Accumulate total duration
-                unsafe {
-                    TOTAL_DURATION += duration;
-                }
-
-                // Log the total accumulated time
-                tracing::info!("Total time so far: {:?}", unsafe { TOTAL_DURATION });
-
-                return Ok(None);  // Return None if no KPIs
+                AnalysisStatus::Incomplete
+            };
+
+            RepositoryReport {
+                application: repo_name.to_string(),
+                source: source_name.clone(),
+                status,
+                products: aggregate_kpi(&kpi_results),  // One verdict per product over all of its occurrences
+                debt: kpi_results,                      // One KPI per occurrence
+                errors,
+                head_commit: config.head_commit.clone(),
+                reused,
             }
         }
         Err(e) => {
-            tracing::trace!("Failed to generate analysis JSON for project '{}', repo '{}': {}", project_name, repo_name, e);
+            tracing::error!("Failed to generate analysis for project '{}', repo '{}': {}", project_name, repo_name, error_chain(e.as_ref()));
+            RepositoryReport {
+                application: repo_name.to_string(),
+                source: source_name.clone(),
+                status: AnalysisStatus::Failed,
+                debt: vec![],
+                products: vec![],
+                errors: vec![AnalysisError::new(None, &e)],
+                head_commit: config.head_commit.clone(),
+                reused: false,
+            }
         }
-    }
+    };
+
+    emit_event(config, || ScanEvent::RepositoryFinished {
+        repository: repo_name.to_string(),
+        status: report.status,
+    });
 
-    // Log the duration in case of failure
+    // Log the duration of this analysis
     let duration = start_time.elapsed();
     tracing::info!(
-        "Analysis failed for project: {}, repo: {} in {:?}",
+        "Analysis {:?} for project: {}, repo: {} in {:?} with {} KPIs and {} errors",
+        report.status,
         project_name,
         repo_name,
-        duration
+        duration,
+        report.debt.len(),
+        report.errors.len()
     );
 
     // Accumulate total duration
-    unsafe {
-        TOTAL_DURATION += duration;
-    }
+    let total_duration = {
+        let mut total = TOTAL_DURATION.lock().unwrap();
+        *total += duration;
+        *total
+    };
 
     // Log the total accumulated time
-    tracing::info!("Total time so far: {:?}", unsafe { TOTAL_DURATION });
+    tracing::info!("Total time so far: {:?}", total_duration);
 
-    Ok(None)
-}
+    // Only a complete analysis without KPIs means there is nothing to record
+    if report.status == AnalysisStatus::Complete && report.debt.is_empty() {
+        tracing::info!("No KPIs to record for project: {}, repo: {}", project_name, repo_name);
+        return Ok(None);
+    }
+
+    let mut final_json = serde_json::to_value(&report)?;
+    remove_null_values(&mut final_json);  // Remove null entries
 
+    tracing::debug!("Json result: {}", &final_json);
+
+    Ok(Some(final_json))
+}
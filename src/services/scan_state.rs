@@ -0,0 +1,159 @@
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+use crate::models::{AppConfig, Analysis};
+use crate::services::get_distinct_dependencies::get_distinct_dependencies;
+use crate::types::MyError;
+
+const SCAN_STATE_TREE: &str = "scan_state";
+
+// Findings of the last complete scan of a repository, reused while its head commit is unchanged
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ScanState {
+    pub head_commit: String,
+    #[serde(default)]
+    pub fingerprint: String, // From `scan_fingerprint`, the findings only hold for the same one
+    pub scanned_at: DateTime<Utc>,
+    pub analyses: Vec<Analysis>,
+}
+
+// Two sources can host a repository with the same project and name
+pub fn state_key(config: &AppConfig, project_name: &str, repo_name: &str) -> String {
+    match &config.source {
+        Some(source) => format!("{}/{}/{}", source.name, project_name, repo_name).to_lowercase(),
+        None => format!("{}/{}", project_name, repo_name).to_lowercase(),
+    }
+}
+
+// Plugins, roadmap products and equivalences the findings depend on: a change of any of them means a new scan.
+// FNV-1a of their sorted values, stable across runs and builds.
+pub fn scan_fingerprint(config: &AppConfig) -> Result<String, MyError> {
+    let plugins = [
+        ("maven", config.enable_maven_analysis),
+        ("npm", config.enable_npm_analysis),
+        ("docker", config.enable_docker_analysis),
+        ("dotnet", config.enable_dotnet_analysis),
+        ("php", config.enable_php_analysis),
+        ("jenkins", config.enable_jenkins_analysis),
+        ("ci", config.enable_ci_analysis),
+    ];
+    let mut products = match config.db.as_ref() {
+        Some(db) => get_distinct_dependencies(db)?,
+        None => vec![],
+    };
+    products.sort();
+    let equivalences: BTreeMap<&String, Vec<&String>> = config
+        .equivalences
+        .iter()
+        .map(|(product, aliases)| {
+            let mut aliases: Vec<&String> = aliases.iter().collect();
+            aliases.sort();
+            (product, aliases)
+        })
+        .collect();
+
+    let canonical = serde_json::to_vec(&(plugins, products, equivalences))?;
+    let hash = canonical
+        .iter()
+        .fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(0x100000001b3));
+    Ok(format!("{:016x}", hash))
+}
+
+pub fn load_scan_state(config: &AppConfig, project_name: &str, repo_name: &str) -> Result<Option<ScanState>, MyError> {
+    let Some(db) = config.db.as_ref() else { return Ok(None) };
+    let tree = db.open_tree(SCAN_STATE_TREE)?;
+
+    match tree.get(state_key(config, project_name, repo_name).as_bytes())? {
+        Some(bytes) => match serde_json::from_slice(&bytes) {
+            Ok(state) => Ok(Some(state)),
+            Err(e) => {
+                warn!("Ignoring unreadable scan state of {}/{}: {}", project_name, repo_name, e);
+                Ok(None)
+            }
+        },
+        None => Ok(None),
+    }
+}
+
+pub fn save_scan_state(config: &AppConfig, project_name: &str, repo_name: &str, state: &ScanState) -> Result<(), MyError> {
+    let Some(db) = config.db.as_ref() else { return Ok(()) };
+    let tree = db.open_tree(SCAN_STATE_TREE)?;
+
+    tree.insert(state_key(config, project_name, repo_name).as_bytes(), serde_json::to_vec(state)?)?;
+    debug!("Saved scan state of {}/{} at {}", project_name, repo_name, state.head_commit);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Roadmap, Roadmaps};
+    use crate::roadmap::persist_to_sled::persist_to_sled;
+
+    fn config() -> AppConfig {
+        AppConfig { db: Some(sled::Config::new().temporary(true).open().unwrap()), ..AppConfig::default() }
+    }
+
+    fn add_product(config: &AppConfig, product: &str) {
+        let roadmap = Roadmap { product: product.to_string(), domain: None, chapter: None, entries: vec![] };
+        persist_to_sled(config.db.as_ref().unwrap(), &Roadmaps { roadmap_list: vec![roadmap] }).unwrap();
+    }
+
+    #[test]
+    fn fingerprint_is_stable() {
+        let config = config();
+        add_product(&config, "java");
+        add_product(&config, "angular");
+        let other = AppConfig { db: config.db.clone(), ..AppConfig::default() };
+        assert_eq!(scan_fingerprint(&config).unwrap(), scan_fingerprint(&config).unwrap());
+        assert_eq!(scan_fingerprint(&config).unwrap(), scan_fingerprint(&other).unwrap());
+    }
+
+    #[test]
+    fn fingerprint_ignores_the_order_of_the_equivalences() {
+        let mut a = config();
+        let mut b = config();
+        a.equivalences.insert("java".to_string(), vec!["openjdk".to_string(), "temurin".to_string()]);
+        b.equivalences.insert("java".to_string(), vec!["temurin".to_string(), "openjdk".to_string()]);
+        assert_eq!(scan_fingerprint(&a).unwrap(), scan_fingerprint(&b).unwrap());
+    }
+
+    #[test]
+    fn fingerprint_changes_with_plugins_products_and_equivalences() {
+        let mut config = config();
+        let initial = scan_fingerprint(&config).unwrap();
+
+        config.enable_npm_analysis = !config.enable_npm_analysis;
+        let plugins = scan_fingerprint(&config).unwrap();
+        assert_ne!(plugins, initial);
+
+        add_product(&config, "java");
+        let products = scan_fingerprint(&config).unwrap();
+        assert_ne!(products, plugins);
+
+        config.equivalences.insert("java".to_string(), vec!["openjdk".to_string()]);
+        let equivalences = scan_fingerprint(&config).unwrap();
+        assert_ne!(equivalences, products);
+
+        config.equivalences.insert("java".to_string(), vec!["openjdk".to_string(), "temurin".to_string()]);
+        assert_ne!(scan_fingerprint(&config).unwrap(), equivalences);
+    }
+
+    #[test]
+    fn scan_state_round_trip() {
+        let config = config();
+        assert!(load_scan_state(&config, "PTEP", "my-repo").unwrap().is_none());
+
+        let state = ScanState { head_commit: "abc123".to_string(), fingerprint: "f".to_string(), scanned_at: Utc::now(), analyses: vec![] };
+        save_scan_state(&config, "PTEP", "My-Repo", &state).unwrap();
+
+        let loaded = load_scan_state(&config, "ptep", "my-repo").unwrap().unwrap();
+        assert_eq!((loaded.head_commit.as_str(), loaded.fingerprint.as_str()), ("abc123", "f"));
+        assert_eq!(loaded.scanned_at, state.scanned_at);
+        assert!(load_scan_state(&config, "PTEP", "other").unwrap().is_none());
+    }
+}
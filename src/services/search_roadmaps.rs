@@ -0,0 +1,20 @@
+use crate::models::{AppConfig, Roadmap};
+use crate::services::list_roadmaps::list_roadmaps;
+use crate::types::MyError;
+
+// Roadmaps whose product or equivalences (names used by the dependencies) contain the text
+pub fn search_roadmaps(config: &AppConfig, db: &sled::Db, text: &str) -> Result<Vec<Roadmap>, MyError> {
+    let text = text.to_lowercase();
+    Ok(list_roadmaps(db)?
+        .into_iter()
+        .filter(|roadmap| {
+            roadmap.product.to_lowercase().contains(&text)
+                || config
+                    .equivalences
+                    .iter()
+                    .filter(|(product, _)| product.eq_ignore_ascii_case(&roadmap.product))
+                    .flat_map(|(_, names)| names)
+                    .any(|name| name.to_lowercase().contains(&text))
+        })
+        .collect())
+}
@@ -1,17 +1,31 @@
 use std::error::Error;
 use std::fmt;
-use anyhow::{anyhow, Context, Result};
 
 pub type MyError = Box<dyn Error + Send + Sync>;
 
 #[derive(Debug)]
 pub enum CustomError {
     NotFound(String),
-    AnalysisFailed(String),
     InvalidInput(String),
     ProjectError(String),
     DatabaseError(String),
     IoError(std::io::Error),
+    // Non-success HTTP response from the platform
+    Http { url: String, status: u16 },
+    // The request never got a response (DNS, TLS, connection reset...)
+    Network { url: String, source: reqwest::Error },
+    // Missing credentials or 401/403 from the platform
+    Auth(String),
+    // Content that could not be parsed (JSON, XML, YAML)
+    Parse { context: String, source: MyError },
+    Config(String),
+    // Failure of one analysis plugin, wrapping its cause
+    Plugin { plugin: String, source: MyError },
+    // Step that failed, wrapping its cause so its kind and status are kept
+    Context { context: String, source: MyError },
+    Database { context: String, source: sled::Error },
+    // Host skipped after too many consecutive failures
+    CircuitOpen { host: String },
 }
 
 impl CustomError {
@@ -27,25 +41,114 @@ impl CustomError {
     pub fn not_found<T: Into<String>>(msg: T) -> MyError {
         Box::new(CustomError::NotFound(msg.into()))
     }
-    pub fn analysis_failed<T: Into<String>>(msg: T) -> MyError {
-        Box::new(CustomError::AnalysisFailed(msg.into()))
+    pub fn http<T: Into<String>>(url: T, status: u16) -> MyError {
+        Box::new(CustomError::Http { url: url.into(), status })
     }
+    pub fn auth<T: Into<String>>(msg: T) -> MyError {
+        Box::new(CustomError::Auth(msg.into()))
+    }
+    pub fn config<T: Into<String>>(msg: T) -> MyError {
+        Box::new(CustomError::Config(msg.into()))
+    }
+    pub fn parse<T: Into<String>, E: Into<MyError>>(context: T, source: E) -> MyError {
+        Box::new(CustomError::Parse { context: context.into(), source: source.into() })
+    }
+    pub fn plugin<T: Into<String>>(plugin: T, source: MyError) -> MyError {
+        Box::new(CustomError::Plugin { plugin: plugin.into(), source })
+    }
+    pub fn context<T: Into<String>>(context: T, source: MyError) -> MyError {
+        Box::new(CustomError::Context { context: context.into(), source })
+    }
+
+    // Innermost typed error, looking through plugin and context wrappers
+    pub fn root<'a>(err: &'a (dyn Error + 'static)) -> Option<&'a CustomError> {
+        match err.downcast_ref::<CustomError>()? {
+            CustomError::Plugin { source, .. } | CustomError::Context { source, .. } => {
+                Self::root(source.as_ref()).or_else(|| err.downcast_ref::<CustomError>())
+            }
+            other => Some(other),
+        }
+    }
+
+    // Failure that may not happen again: network, circuit breaker, storage, 429 and 5xx
+    pub fn is_transient(err: &(dyn Error + 'static)) -> bool {
+        match err.downcast_ref::<CustomError>() {
+            Some(CustomError::Network { .. })
+            | Some(CustomError::CircuitOpen { .. })
+            | Some(CustomError::IoError(_))
+            | Some(CustomError::DatabaseError(_))
+            | Some(CustomError::Database { .. }) => true,
+            Some(CustomError::Http { status, .. }) => matches!(status, 429 | 500 | 502 | 503 | 504),
+            Some(CustomError::Plugin { source, .. }) | Some(CustomError::Context { source, .. }) => {
+                Self::is_transient(source.as_ref())
+            }
+            _ => false,
+        }
+    }
+
+    // Short machine readable category, used in the JSON outputs
+    pub fn kind(err: &(dyn Error + 'static)) -> &'static str {
+        match err.downcast_ref::<CustomError>() {
+            Some(CustomError::NotFound(_)) => "not_found",
+            Some(CustomError::InvalidInput(_)) => "invalid_input",
+            Some(CustomError::ProjectError(_)) => "project",
+            Some(CustomError::DatabaseError(_)) | Some(CustomError::Database { .. }) => "database",
+            Some(CustomError::IoError(_)) => "io",
+            Some(CustomError::Http { .. }) => "http",
+            Some(CustomError::Network { .. }) => "network",
+            Some(CustomError::Auth(_)) => "auth",
+            Some(CustomError::Parse { .. }) => "parse",
+            Some(CustomError::Config(_)) => "config",
+            Some(CustomError::CircuitOpen { .. }) => "circuit_open",
+            Some(CustomError::Plugin { source, .. }) | Some(CustomError::Context { source, .. }) => Self::kind(source.as_ref()),
+            None => "other",
+        }
+    }
+}
+
+// Full "error: cause: cause" message following the source chain
+pub fn error_chain(err: &(dyn Error + 'static)) -> String {
+    let mut message = err.to_string();
+    let mut source = err.source();
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    message
 }
 
 impl fmt::Display for CustomError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::NotFound(msg) => write!(f, "Not found: {}", msg),
-            Self::AnalysisFailed(msg) => write!(f, "Analysis failed: {}", msg),
             Self::InvalidInput(msg) => write!(f, "Invalid input: {}", msg),
             Self::ProjectError(msg) => write!(f, "Project error: {}", msg),
             Self::DatabaseError(msg) => write!(f, "Database error: {}", msg),
             Self::IoError(e) => write!(f, "IO error: {}", e),
+            Self::Http { url, status } => write!(f, "HTTP {} for {}", status, url),
+            Self::Network { url, .. } => write!(f, "Request to {} failed", url),
+            Self::Auth(msg) => write!(f, "Authentication error: {}", msg),
+            Self::Parse { context, .. } => write!(f, "Failed to parse {}", context),
+            Self::Config(msg) => write!(f, "Configuration error: {}", msg),
+            Self::Plugin { plugin, .. } => write!(f, "Plugin {} failed", plugin),
+            Self::Context { context, .. } => write!(f, "{}", context),
+            Self::Database { context, .. } => write!(f, "Database error: {}", context),
+            Self::CircuitOpen { host } => write!(f, "Circuit breaker open for {}, request skipped", host),
         }
     }
 }
 
-impl Error for CustomError {}
+impl Error for CustomError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::IoError(e) => Some(e),
+            Self::Network { source, .. } => Some(source),
+            Self::Parse { source, .. } | Self::Plugin { source, .. } | Self::Context { source, .. } => Some(source.as_ref()),
+            Self::Database { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
 
 impl From<std::io::Error> for CustomError {
     fn from(err: std::io::Error) -> Self {
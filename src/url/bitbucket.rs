@@ -1,21 +1,23 @@
-use crate::url::{UrlMode, UrlConfig};
+use crate::url::{set_query_param, UrlMode, UrlConfig};
 
-use base64::{engine::general_purpose, Engine as _};
-use dotenv::dotenv;
-use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION};
-use std::env;
+// Largest page size accepted by Bitbucket Server by default
+const PAGE_LIMIT: &str = "100";
+
+use reqwest::header::{HeaderName, HeaderValue};
+use crate::credentials::Credentials;
 use crate::types::MyError;
-use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug)]
 pub struct BitbucketConfig {
     pub base_url: String,
+    pub credentials: Credentials, // Resolved at boot
 }
 
 impl UrlConfig for BitbucketConfig {
 
-    fn raw_file_url(&self, project_name: &str, repo_name: &str, file_path: &str) -> String {
-        self.file_url(UrlMode::Raw, project_name, repo_name, file_path, Some("master"))
+    fn raw_file_url(&self, project_name: &str, repo_name: &str, file_path: &str, branch: Option<&str>) -> String {
+        self.file_url(UrlMode::Raw, project_name, repo_name, file_path, branch)
     }
 
     // Common function for both raw and browse URLs, with branch parameter
@@ -23,6 +25,7 @@ impl UrlConfig for BitbucketConfig {
         let branch = branch.unwrap_or("master"); // Default to "master" if not provided
         let mode_str = match mode {
             UrlMode::Raw => "raw",
+            UrlMode::Browse => "browse",
         };
 
         format!(
@@ -31,6 +34,25 @@ impl UrlConfig for BitbucketConfig {
         )
     }
 
+    // Lists the files below a directory (paths are relative to that directory)
+    fn files_url(&self, project_name: &str, repo_name: &str, dir_path: &str, branch: Option<&str>) -> String {
+        format!(
+            "{}/rest/api/1.0/projects/{}/repos/{}/files/{}?at=refs/heads/{}",
+            self.base_url, project_name, repo_name, dir_path, branch.unwrap_or("master")
+        )
+    }
+
+    fn latest_commit_url(&self, project_name: &str, repo_name: &str, branch: Option<&str>) -> String {
+        format!(
+            "{}/rest/api/1.0/projects/{}/repos/{}/commits?until=refs/heads/{}&limit=1",
+            self.base_url, project_name, repo_name, branch.unwrap_or("master")
+        )
+    }
+
+    fn parse_latest_commit(&self, response: &Value) -> Option<String> {
+        response["values"][0]["id"].as_str().map(str::to_string)
+    }
+
     fn projects_url(&self) -> String {
         format!("{}/rest/api/1.0/projects", self.base_url)
     }
@@ -39,23 +61,26 @@ impl UrlConfig for BitbucketConfig {
         format!("{}/rest/api/1.0/projects/{}/repos", self.base_url, project_name)
     }
 
-    // Function for getting headers
-    fn get_headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>, MyError> {
-        dotenv().ok(); // Load environment variables
+    fn first_page_url(&self, url: &str) -> String {
+        set_query_param(&set_query_param(url, "start", "0"), "limit", PAGE_LIMIT)
+    }
 
-        let username = env::var("BITBUCKET_USERNAME")
-            .map_err(|e| format!("Missing BITBUCKET_USERNAME: {}", e))?;
-        let password = env::var("BITBUCKET_PASSWORD")
-            .map_err(|e| format!("Missing BITBUCKET_PASSWORD: {}", e))?;
+    fn page_items(&self, response: &Value) -> Vec<Value> {
+        response["values"].as_array().cloned().unwrap_or_default()
+    }
 
-        let auth_value = format!(
-            "Basic {}",
-            general_purpose::STANDARD.encode(format!("{}:{}", username, password))
-        );
+    // Paged APIs answer { "isLastPage": false, "nextPageStart": 25, "values": [...] }
+    fn next_page_url(&self, url: &str, response: &Value, _link_header: Option<&str>) -> Option<String> {
+        if response["isLastPage"].as_bool().unwrap_or(true) {
+            return None;
+        }
+        let next_start = response["nextPageStart"].as_u64()?;
+        Some(set_query_param(url, "start", &next_start.to_string()))
+    }
 
-        Ok(vec![
-            (AUTHORIZATION, HeaderValue::from_str(&auth_value)?),
-        ])
+    // Function for getting headers
+    fn get_headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>, MyError> {
+        Ok(self.credentials.authorization_header()?.into_iter().collect())
     }
 }
 
@@ -0,0 +1,108 @@
+use crate::url::{set_query_param, UrlMode, UrlConfig};
+
+use reqwest::header::{HeaderName, HeaderValue};
+use crate::credentials::Credentials;
+use crate::types::MyError;
+use serde_json::Value;
+
+// bitbucket.org, API 2.0: https://api.bitbucket.org/2.0
+#[derive(Debug)]
+pub struct BitbucketCloudConfig {
+    pub base_url: String,
+    pub credentials: Credentials, // Resolved at boot
+}
+
+impl BitbucketCloudConfig {
+    // api.bitbucket.org/2.0 is browsed on bitbucket.org
+    fn web_url(&self) -> String {
+        self.base_url
+            .trim_end_matches('/')
+            .trim_end_matches("/2.0")
+            .replace("://api.", "://")
+    }
+}
+
+impl UrlConfig for BitbucketCloudConfig {
+
+    fn raw_file_url(&self, workspace: &str, repo_slug: &str, file_path: &str, branch: Option<&str>) -> String {
+        self.file_url(UrlMode::Raw, workspace, repo_slug, file_path, branch)
+    }
+
+    // `src/{commit}/{path}` accepts a commit, a branch or HEAD (main branch)
+    fn file_url(&self, mode: UrlMode, workspace: &str, repo_slug: &str, file_path: &str, branch: Option<&str>) -> String {
+        let commit = branch.unwrap_or("HEAD");
+        match mode {
+            UrlMode::Raw => format!(
+                "{}/repositories/{}/{}/src/{}/{}",
+                self.base_url, workspace, repo_slug, commit, file_path
+            ),
+            UrlMode::Browse => format!(
+                "{}/{}/{}/src/{}/{}",
+                self.web_url(), workspace, repo_slug, commit, file_path
+            ),
+        }
+    }
+
+    // A directory path ending with '/' answers a paged listing of its entries
+    fn files_url(&self, workspace: &str, repo_slug: &str, dir_path: &str, branch: Option<&str>) -> String {
+        format!(
+            "{}/repositories/{}/{}/src/{}/{}/",
+            self.base_url, workspace, repo_slug, branch.unwrap_or("HEAD"), dir_path.trim_end_matches('/')
+        )
+    }
+
+    fn latest_commit_url(&self, workspace: &str, repo_slug: &str, branch: Option<&str>) -> String {
+        format!(
+            "{}/repositories/{}/{}/commits/{}?pagelen=1",
+            self.base_url, workspace, repo_slug, branch.unwrap_or("HEAD")
+        )
+    }
+
+    fn parse_latest_commit(&self, response: &Value) -> Option<String> {
+        response["values"][0]["hash"].as_str().map(str::to_string)
+    }
+
+    // Workspaces the credentials have access to, used when none is configured
+    fn projects_url(&self) -> String {
+        format!("{}/user/permissions/workspaces", self.base_url)
+    }
+
+    // Repositories of a workspace
+    fn repos_url(&self, workspace: &str, _: &str) -> String {
+        format!("{}/repositories/{}", self.base_url, workspace)
+    }
+
+    // URLs use the slug, the name may contain spaces and capitals
+    fn repo_name(&self, repo: &Value) -> Option<String> {
+        repo["slug"].as_str().map(str::to_string)
+    }
+
+    fn repo_owner(&self, workspace: &str, repo: &Value) -> String {
+        repo["workspace"]["slug"]
+            .as_str()
+            .unwrap_or(workspace)
+            .to_string()
+    }
+
+    fn line_anchor(&self, line: usize) -> String {
+        format!("#lines-{}", line)
+    }
+
+    fn first_page_url(&self, url: &str) -> String {
+        set_query_param(url, "pagelen", "100")
+    }
+
+    fn page_items(&self, response: &Value) -> Vec<Value> {
+        response["values"].as_array().cloned().unwrap_or_default()
+    }
+
+    // Paged APIs answer { "next": "<url of the next page>", "values": [...] }
+    fn next_page_url(&self, _url: &str, response: &Value, _link_header: Option<&str>) -> Option<String> {
+        response["next"].as_str().map(str::to_string)
+    }
+
+    // OAuth access token, or username and app password
+    fn get_headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>, MyError> {
+        Ok(self.credentials.authorization_header()?.into_iter().collect())
+    }
+}
@@ -1,52 +1,164 @@
 
-use dotenv::dotenv;
-use reqwest::header::{HeaderName, HeaderValue, AUTHORIZATION, USER_AGENT};
-use std::env;
+use reqwest::header::{HeaderName, HeaderValue, USER_AGENT};
+use crate::credentials::Credentials;
 use crate::types::MyError;
-use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use crate::url::{UrlMode, UrlConfig};
+use crate::url::{set_query_param, UrlMode, UrlConfig};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug)]
 pub struct GithubConfig {
     pub base_url: String,
     pub user: String,
+    pub credentials: Credentials, // Resolved at boot
+}
+
+impl GithubConfig {
+    // api.github.com is served from github.com, GitHub Enterprise from <host>/api/v3
+    fn web_url(&self) -> String {
+        if self.base_url.contains("api.github.com") {
+            "https://github.com".to_string()
+        } else {
+            self.base_url.trim_end_matches('/').trim_end_matches("/api/v3").to_string()
+        }
+    }
+
+    // Empty owners fall back to the configured user
+    fn owner<'a>(&'a self, owner: &'a str) -> &'a str {
+        if owner.is_empty() { &self.user } else { owner }
+    }
+}
+
+// The 'contents' endpoint reads the default branch unless `ref` is given
+fn with_ref(url: String, branch: Option<&str>) -> String {
+    match branch {
+        Some(branch) => set_query_param(&url, "ref", branch),
+        None => url,
+    }
 }
 
 impl UrlConfig for GithubConfig {
-    // URL for user's repositories
+    // Repositories the token has access to, used when no organisation or team is configured
     fn projects_url(&self) -> String {
         format!("{}/user/repos", self.base_url)
     }
 
-    // URL for accessing a specific repository
-    fn repos_url(&self, _owner: &str, repo: &str) -> String {
-        format!("{}/{}/{}", self.base_url, self.user, repo)
+    // Repositories of a scope: "org", "org/team", or the configured user
+    fn repos_url(&self, scope: &str, _repo: &str) -> String {
+        match scope.split_once('/') {
+            Some((org, team)) => format!("{}/orgs/{}/teams/{}/repos", self.base_url, org, team),
+            None if scope.is_empty() || scope == self.user => self.projects_url(),
+            None => format!("{}/orgs/{}/repos", self.base_url, scope),
+        }
+    }
+
+    // File content through the 'contents' endpoint (raw media type, see accept_header)
+    fn raw_file_url(&self, owner: &str, repo: &str, file_path: &str, branch: Option<&str>) -> String {
+        self.file_url(UrlMode::Raw, owner, repo, file_path, branch)
+    }
+
+    // Raw goes through the 'contents' API, Browse points to the web UI
+    fn file_url(&self, mode: UrlMode, owner: &str, repo: &str, file_path: &str, branch: Option<&str>) -> String {
+        let owner = self.owner(owner);
+        match mode {
+            UrlMode::Raw => with_ref(format!("{}/repos/{}/{}/contents/{}", self.base_url, owner, repo, file_path), branch),
+            UrlMode::Browse => format!(
+                "{}/{}/{}/blob/{}/{}",
+                self.web_url(), owner, repo, branch.unwrap_or("HEAD"), file_path
+            ),
+        }
+    }
+
+    // The 'contents' endpoint returns an array of entries when pointed at a directory
+    fn files_url(&self, owner: &str, repo: &str, dir_path: &str, branch: Option<&str>) -> String {
+        with_ref(format!("{}/repos/{}/{}/contents/{}", self.base_url, self.owner(owner), repo, dir_path), branch)
+    }
+
+    fn latest_commit_url(&self, owner: &str, repo: &str, branch: Option<&str>) -> String {
+        format!(
+            "{}/repos/{}/{}/commits/{}",
+            self.base_url, self.owner(owner), repo, branch.unwrap_or("HEAD")
+        )
     }
 
-    // Unified URL for accessing file content (GitHub uses 'contents' endpoint for raw access)
-    fn raw_file_url(&self, _owner: &str, repo: &str, file_path: &str) -> String {
-        self.file_url(UrlMode::Raw, _owner, repo, file_path, None)
+    fn parse_latest_commit(&self, response: &Value) -> Option<String> {
+        response["sha"].as_str().map(str::to_string)
     }
 
-    // For GitHub, the `file_url` and `raw_file_url` can be the same (accessing contents)
-    fn file_url(&self, _mode: UrlMode, _owner: &str, repo: &str, file_path: &str, _branch: Option<&str>) -> String {
-        format!("{}/{}/{}/contents/{}", self.base_url, self.user, repo, file_path)
+    // Scopes like "org/team" list repositories of several owners
+    fn repo_owner(&self, scope: &str, repo: &Value) -> String {
+        repo["owner"]["login"]
+            .as_str()
+            .map(str::to_string)
+            .unwrap_or_else(|| self.owner(scope).to_string())
+    }
+
+    // File contents as is instead of the base64 JSON envelope (directories still answer JSON)
+    fn accept_header(&self, url: &str) -> Option<HeaderValue> {
+        if url.contains("/contents/") {
+            Some(HeaderValue::from_static("application/vnd.github.raw"))
+        } else {
+            Some(HeaderValue::from_static("application/vnd.github+json"))
+        }
+    }
+
+    fn first_page_url(&self, url: &str) -> String {
+        set_query_param(url, "per_page", "100")
+    }
+
+    // Listing endpoints answer an array, `contents` answers an object for a single file
+    fn page_items(&self, response: &Value) -> Vec<Value> {
+        response.as_array().cloned().unwrap_or_default()
+    }
+
+    // Link: <https://api.github.com/user/repos?page=2>; rel="next", <...>; rel="last"
+    fn next_page_url(&self, _url: &str, _response: &Value, link_header: Option<&str>) -> Option<String> {
+        link_header?.split(',').find_map(|link| {
+            let (target, params) = link.split_once(';')?;
+            params
+                .split(';')
+                .any(|param| param.trim() == "rel=\"next\"")
+                .then(|| target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+        })
+    }
+
+    fn line_anchor(&self, line: usize) -> String {
+        format!("#L{}", line)
     }
 
     // Method to get necessary headers, including GitHub token and user agent
     fn get_headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>, MyError> {
-        dotenv().ok(); // Load environment variables
+        let mut headers: Vec<(HeaderName, HeaderValue)> = self.credentials.authorization_header()?.into_iter().collect();
+        headers.push((USER_AGENT, HeaderValue::from_str("bennekrouf")?));
+        Ok(headers)
+    }
+}
 
-        let github_token = env::var("GITHUB_TOKEN")
-            .map_err(|e| format!("Missing GITHUB_TOKEN: {}", e))?;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        let auth_value = format!("Bearer {}", github_token);
-        let user_agent_value = HeaderValue::from_str("bennekrouf")?;
+    fn next_page_url(link_header: Option<&str>) -> Option<String> {
+        let config = GithubConfig {
+            base_url: "https://api.github.com".to_string(),
+            user: "me".to_string(),
+            credentials: Credentials::Anonymous,
+        };
+        config.next_page_url("https://api.github.com/user/repos", &Value::Null, link_header)
+    }
+
+    #[test]
+    fn next_link_among_the_others() {
+        let link = "<https://api.github.com/user/repos?page=1>; rel=\"prev\", \
+                    <https://api.github.com/user/repos?page=3>; rel=\"next\", \
+                    <https://api.github.com/user/repos?page=5>; rel=\"last\"";
+        assert_eq!(next_page_url(Some(link)).as_deref(), Some("https://api.github.com/user/repos?page=3"));
+    }
 
-        Ok(vec![
-            (AUTHORIZATION, HeaderValue::from_str(&auth_value)?),
-            (USER_AGENT, user_agent_value),
-        ])
+    #[test]
+    fn no_next_link_on_the_last_page() {
+        let link = "<https://api.github.com/user/repos?page=1>; rel=\"first\", <https://api.github.com/user/repos?page=4>; rel=\"prev\"";
+        assert_eq!(next_page_url(Some(link)), None);
+        assert_eq!(next_page_url(None), None);
     }
 }
@@ -1,11 +1,14 @@
 pub mod bitbucket;
+pub mod bitbucket_cloud;
 pub mod github;
 
 pub enum UrlMode {
     Raw,
+    Browse,
 }
 
 use reqwest::header::{HeaderName, HeaderValue};
+use serde_json::Value;
 use std::fmt::Debug;
 use crate::types::MyError;
 
@@ -13,8 +16,52 @@ use crate::types::MyError;
 pub trait UrlConfig: Send + Sync + Debug {
     fn projects_url(&self) -> String;
     fn repos_url(&self, owner: &str, repo: &str) -> String;
-    fn raw_file_url(&self, owner: &str, repo: &str, file_path: &str) -> String;
+    fn raw_file_url(&self, owner: &str, repo: &str, file_path: &str, branch: Option<&str>) -> String;
     fn file_url(&self, mode: UrlMode, owner: &str, repo: &str, file_path: &str, branch: Option<&str>) -> String;
+    fn files_url(&self, owner: &str, repo: &str, dir_path: &str, branch: Option<&str>) -> String;
+    fn latest_commit_url(&self, owner: &str, repo: &str, branch: Option<&str>) -> String;
+    fn parse_latest_commit(&self, response: &Value) -> Option<String>;
     fn get_headers(&self) -> Result<Vec<(HeaderName, HeaderValue)>, MyError>;
+
+    // Pagination: URL of the first page, items of a page and URL of the next one
+    fn first_page_url(&self, url: &str) -> String;
+    fn page_items(&self, response: &Value) -> Vec<Value>;
+    fn next_page_url(&self, url: &str, response: &Value, link_header: Option<&str>) -> Option<String>;
+
+    // Name of a listed repository as used in URLs
+    fn repo_name(&self, repo: &Value) -> Option<String> {
+        repo["name"].as_str().map(str::to_string)
+    }
+
+    // Owner to use in the file URLs of a repository listed under `scope`
+    fn repo_owner(&self, scope: &str, _repo: &Value) -> String {
+        scope.to_string()
+    }
+
+    // Media type requested for a URL, `None` keeps the platform default
+    fn accept_header(&self, _url: &str) -> Option<HeaderValue> {
+        None
+    }
+
+    // Fragment appended to a browse URL to highlight a line
+    fn line_anchor(&self, line: usize) -> String {
+        format!("#{}", line)
+    }
 }
 
+
+// Set (or replace) a query parameter of a URL
+pub fn set_query_param(url: &str, key: &str, value: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            let pairs: Vec<(String, String)> = parsed
+                .query_pairs()
+                .filter(|(k, _)| k != key)
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect();
+            parsed.query_pairs_mut().clear().extend_pairs(pairs).append_pair(key, value);
+            parsed.to_string()
+        }
+        Err(_) => url.to_string(),
+    }
+}
@@ -7,8 +7,8 @@ use tracing::{info, error, debug};
 use crate::models::AppConfig;
 use crate::types::MyError;
 
-pub fn append_json_to_file<'a>(
-    config: &'a AppConfig,
+pub fn append_json_to_file(
+    config: &AppConfig,
     project_name: &str,
     json_data: &Value) -> Result<(), MyError> {
     // Get the target folder from the environment, default to "tmp" if not set
@@ -18,7 +18,7 @@ pub fn append_json_to_file<'a>(
     // Ensure the target folder exists
     if !Path::new(&output_folder).exists() {
         info!("Target folder '{}' does not exist, creating it.", output_folder);
-        create_dir_all(&output_folder)
+        create_dir_all(output_folder)
             .map_err(|e| {
                 error!("Error creating target folder '{}': {}", output_folder, e);
                 format!("Failed to create target folder '{}': {}", output_folder, e)
@@ -14,7 +14,7 @@ pub async fn check_file_exists(
     let url_config = &*config.url_config;
 
     // Construct the Bitbucket API URL to check the file
-    let file_url = url_config.raw_file_url(project_name, repo_name, file_path);
+    let file_url = url_config.raw_file_url(project_name, repo_name, file_path, config.branch.as_deref());
 
     info!("Checking for file {} at URL: {}", file_path, file_url);
 
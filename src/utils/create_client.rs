@@ -0,0 +1,15 @@
+use reqwest::Client;
+
+use std::time::Duration;
+use crate::models::HttpConfig;
+use crate::types::MyError;
+
+/// HTTP client shared by every source, credentials are attached per request by the `UrlConfig`
+pub fn create_client(http: &HttpConfig) -> Result<Client, MyError> {
+    // Create the HTTP client with the configured timeouts
+    let client = Client::builder()
+        .timeout(Duration::from_secs(http.timeout_secs))
+        .connect_timeout(Duration::from_secs(http.connect_timeout_secs))
+        .build()?;
+    Ok(client)
+}
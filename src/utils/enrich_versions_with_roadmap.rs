@@ -17,7 +17,7 @@ pub async fn enrich_versions_with_roadmap(
         trace!("Looking for roadmap for product : {}", product);
 
         // Get the roadmap for the product from the sled DB
-        let roadmap = match get_roadmap(&db, product)? {
+        let roadmap = match get_roadmap(db, product)? {
             Some(roadmap) => roadmap,  // Return the Roadmap struct
             None => {
                 info!("No roadmap found for product : {}", product);
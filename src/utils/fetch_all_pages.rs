@@ -0,0 +1,37 @@
+
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::models::AppConfig;
+use crate::utils::run_get_request::run_get_response;
+use crate::types::{CustomError, MyError};
+
+/// Follow the platform pagination (Bitbucket `nextPageStart`, GitHub `Link` header)
+/// and return the items of every page.
+pub async fn fetch_all_pages(config: &AppConfig, url: &str) -> Result<Vec<Value>, MyError> {
+    let url_config = &*config.url_config;
+    let mut page_url = url_config.first_page_url(url);
+    let mut items = Vec::new();
+    let mut pages = 0;
+
+    loop {
+        debug!("Fetching page {}", page_url);
+        let Some(response) = run_get_response(config, &page_url).await? else {
+            break; // 404, nothing to list
+        };
+
+        let json: Value = serde_json::from_str(&response.body)
+            .map_err(|e| CustomError::parse(format!("JSON from {}", page_url), e))?;
+        items.extend(url_config.page_items(&json));
+        pages += 1;
+
+        match url_config.next_page_url(&page_url, &json, response.link.as_deref()) {
+            // A next page pointing to itself would loop forever
+            Some(next_url) if next_url != page_url => page_url = next_url,
+            _ => break,
+        }
+    }
+
+    info!("Fetched {} items in {} page(s) from {}", items.len(), pages, url);
+    Ok(items)
+}
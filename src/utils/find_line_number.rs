@@ -0,0 +1,11 @@
+
+/// 1-based number of the first line of `content` containing `needle`.
+pub fn find_line_number(content: &str, needle: &str) -> Option<usize> {
+    if needle.is_empty() {
+        return None;
+    }
+    content
+        .lines()
+        .position(|line| line.contains(needle))
+        .map(|index| index + 1)
+}
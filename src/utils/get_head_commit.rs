@@ -0,0 +1,20 @@
+
+use tracing::debug;
+
+use crate::models::AppConfig;
+use crate::utils::run_json_get_query::run_json_get_query;
+use crate::types::MyError;
+
+/// Latest commit of the analysed branch (default one when not set), `None` when the platform does not tell
+pub async fn get_head_commit(
+    config: &AppConfig,
+    project_name: &str,
+    repo_name: &str,
+) -> Result<Option<String>, MyError> {
+    let commit_url = config.url_config.latest_commit_url(project_name, repo_name, config.branch.as_deref());
+    let response = run_json_get_query(config, &commit_url).await?;
+    let commit = config.url_config.parse_latest_commit(&response);
+
+    debug!("Head commit of {}/{}: {:?}", project_name, repo_name, commit);
+    Ok(commit)
+}
@@ -0,0 +1,157 @@
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::models::AppConfig;
+use crate::types::MyError;
+
+const HTTP_CACHE_TREE: &str = "http_cache";
+// Head commit of each repository whose files are cached
+const HTTP_CACHE_HEADS_TREE: &str = "http_cache_heads";
+
+// A successful response body with the validators needed for conditional requests
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CachedResponse {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    pub body: String,
+    // `Link` header, GitHub uses it for pagination
+    #[serde(default)]
+    pub link: Option<String>,
+    // Refreshed by a 304, entries older than `http.cache_ttl_days` are removed
+    #[serde(default)]
+    pub stored_at: Option<DateTime<Utc>>,
+}
+
+// Content fetched at a known commit never changes, so the commit is part of the key.
+// It comes first, the entries of a commit are removed by prefix once it is no longer the head.
+pub fn cache_key(url: &str, commit: Option<&str>) -> String {
+    match commit {
+        Some(commit) => format!("{}#{}", commit, url),
+        None => url.to_string(),
+    }
+}
+
+pub fn get_cached_response(config: &AppConfig, key: &str) -> Option<CachedResponse> {
+    let tree = config.db.as_ref()?.open_tree(HTTP_CACHE_TREE).ok()?;
+    let bytes = tree.get(key.as_bytes()).ok()??;
+    match serde_json::from_slice(&bytes) {
+        Ok(cached) => Some(cached),
+        Err(e) => {
+            warn!("Ignoring corrupted cache entry for {}: {}", key, e);
+            None
+        }
+    }
+}
+
+pub fn store_cached_response(config: &AppConfig, key: &str, cached: &CachedResponse) {
+    let Some(db) = config.db.as_ref() else { return };
+
+    let result = db
+        .open_tree(HTTP_CACHE_TREE)
+        .and_then(|tree| {
+            let bytes = serde_json::to_vec(cached).unwrap_or_default();
+            tree.insert(key.as_bytes(), bytes).map(|_| ())
+        });
+
+    match result {
+        Ok(()) => debug!("Cached response for {}", key),
+        Err(e) => warn!("Failed to cache response for {}: {}", key, e),
+    }
+}
+
+// A new head commit makes the files cached at the previous one useless
+pub fn forget_previous_commit(config: &AppConfig, repository: &str, head_commit: &str) -> Result<(), MyError> {
+    let Some(db) = config.db.as_ref() else { return Ok(()) };
+    let heads = db.open_tree(HTTP_CACHE_HEADS_TREE)?;
+
+    let Some(previous) = heads.insert(repository.as_bytes(), head_commit.as_bytes())? else { return Ok(()) };
+    if previous.as_ref() == head_commit.as_bytes() {
+        return Ok(());
+    }
+    let tree = db.open_tree(HTTP_CACHE_TREE)?;
+    let mut prefix = previous.to_vec();
+    prefix.push(b'#');
+    let mut removed = 0;
+    for key in tree.scan_prefix(&prefix).keys() {
+        tree.remove(key?)?;
+        removed += 1;
+    }
+    debug!("{} cached files of {} at its previous commit removed", removed, repository);
+    Ok(())
+}
+
+// Entries stored before `http.cache_ttl_days`, and the ones without a date
+pub fn prune_http_cache(config: &AppConfig) -> Result<(), MyError> {
+    let Some(db) = config.db.as_ref() else { return Ok(()) };
+    let tree = db.open_tree(HTTP_CACHE_TREE)?;
+    let oldest = Utc::now() - Duration::days(config.http.cache_ttl_days.min(36_500) as i64);
+
+    let mut removed = 0;
+    for entry in tree.iter() {
+        let (key, bytes) = entry?;
+        let expired = serde_json::from_slice::<CachedResponse>(&bytes)
+            .ok()
+            .and_then(|cached| cached.stored_at)
+            .is_none_or(|stored_at| stored_at < oldest);
+        if expired {
+            tree.remove(key)?;
+            removed += 1;
+        }
+    }
+    if removed > 0 {
+        info!("{} expired HTTP cache entries removed", removed);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> AppConfig {
+        AppConfig { db: Some(sled::Config::new().temporary(true).open().unwrap()), ..AppConfig::default() }
+    }
+
+    fn cached(body: &str, stored_at: Option<DateTime<Utc>>) -> CachedResponse {
+        CachedResponse { etag: None, last_modified: None, body: body.to_string(), link: None, stored_at }
+    }
+
+    #[test]
+    fn cache_key_starts_with_the_commit() {
+        assert_eq!(cache_key("https://host/a/pom.xml", Some("abc123")), "abc123#https://host/a/pom.xml");
+        assert_eq!(cache_key("https://host/repos", None), "https://host/repos");
+    }
+
+    #[test]
+    fn new_head_commit_forgets_the_files_of_the_previous_one() {
+        let config = config();
+        for key in [cache_key("https://host/a/pom.xml", Some("old")), cache_key("https://host/b/pom.xml", Some("older"))] {
+            store_cached_response(&config, &key, &cached("<project/>", Some(Utc::now())));
+        }
+        store_cached_response(&config, "https://host/repos", &cached("[]", Some(Utc::now())));
+
+        forget_previous_commit(&config, "a", "old").unwrap();
+        assert!(get_cached_response(&config, &cache_key("https://host/a/pom.xml", Some("old"))).is_some());
+
+        forget_previous_commit(&config, "a", "new").unwrap();
+        assert!(get_cached_response(&config, &cache_key("https://host/a/pom.xml", Some("old"))).is_none());
+        assert!(get_cached_response(&config, &cache_key("https://host/b/pom.xml", Some("older"))).is_some());
+        assert!(get_cached_response(&config, "https://host/repos").is_some());
+    }
+
+    #[test]
+    fn prune_removes_the_entries_older_than_the_ttl() {
+        let mut config = config();
+        config.http.cache_ttl_days = 30;
+        store_cached_response(&config, "fresh", &cached("", Some(Utc::now() - Duration::days(29))));
+        store_cached_response(&config, "expired", &cached("", Some(Utc::now() - Duration::days(31))));
+        store_cached_response(&config, "undated", &cached("", None));
+
+        prune_http_cache(&config).unwrap();
+        assert!(get_cached_response(&config, "fresh").is_some());
+        assert!(get_cached_response(&config, "expired").is_none());
+        assert!(get_cached_response(&config, "undated").is_none());
+    }
+}
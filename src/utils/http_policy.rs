@@ -0,0 +1,177 @@
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use tracing::{info, warn};
+
+use crate::models::HttpConfig;
+
+/// Counters of the HTTP activity of a run, logged in the run summary
+#[derive(Debug, Default)]
+pub struct HttpStats {
+    pub requests: AtomicU64,
+    pub retries: AtomicU64,
+    pub rate_limited: AtomicU64,
+    pub failures: AtomicU64,
+    pub circuit_rejections: AtomicU64,
+    pub cache_hits: AtomicU64,
+    pub not_modified: AtomicU64,
+}
+
+// Values of the counters at one time, the stats are shared by every run of a server
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HttpCounts {
+    pub requests: u64,
+    pub retries: u64,
+    pub rate_limited: u64,
+    pub failures: u64,
+    pub circuit_rejections: u64,
+    pub cache_hits: u64,
+    pub not_modified: u64,
+}
+
+impl HttpStats {
+    pub fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn snapshot(&self) -> HttpCounts {
+        HttpCounts {
+            requests: self.requests.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            failures: self.failures.load(Ordering::Relaxed),
+            circuit_rejections: self.circuit_rejections.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            not_modified: self.not_modified.load(Ordering::Relaxed),
+        }
+    }
+
+    // Activity since `start`, taken when the run began
+    pub fn log_summary(&self, start: &HttpCounts) {
+        let now = self.snapshot();
+        info!(
+            "HTTP summary: {} requests, {} retries, {} rate limited, {} failures, {} rejected by circuit breaker, {} cache hits, {} not modified",
+            now.requests.saturating_sub(start.requests),
+            now.retries.saturating_sub(start.retries),
+            now.rate_limited.saturating_sub(start.rate_limited),
+            now.failures.saturating_sub(start.failures),
+            now.circuit_rejections.saturating_sub(start.circuit_rejections),
+            now.cache_hits.saturating_sub(start.cache_hits),
+            now.not_modified.saturating_sub(start.not_modified),
+        );
+    }
+}
+
+#[derive(Debug, Default)]
+struct BreakerState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// One circuit breaker per host: after `circuit_breaker_threshold` consecutive failures
+/// the host is skipped until the cooldown is over.
+#[derive(Debug, Default)]
+pub struct CircuitBreakers {
+    hosts: Mutex<HashMap<String, BreakerState>>,
+}
+
+impl CircuitBreakers {
+    pub fn is_open(&self, host: &str) -> bool {
+        let hosts = self.hosts.lock().unwrap();
+        hosts
+            .get(host)
+            .and_then(|state| state.open_until)
+            .map(|until| Instant::now() < until)
+            .unwrap_or(false)
+    }
+
+    pub fn record_success(&self, host: &str) {
+        let mut hosts = self.hosts.lock().unwrap();
+        hosts.remove(host);
+    }
+
+    pub fn record_failure(&self, host: &str, policy: &HttpConfig) {
+        let mut hosts = self.hosts.lock().unwrap();
+        let state = hosts.entry(host.to_string()).or_default();
+        state.consecutive_failures += 1;
+
+        if policy.circuit_breaker_threshold > 0 && state.consecutive_failures >= policy.circuit_breaker_threshold {
+            warn!(
+                "Circuit breaker opened for {} after {} consecutive failures",
+                host, state.consecutive_failures
+            );
+            state.open_until = Some(Instant::now() + Duration::from_secs(policy.circuit_breaker_cooldown_secs));
+        }
+    }
+}
+
+// Exponential backoff with full jitter: random delay in [0, min(max, initial * 2^attempt)]
+pub fn backoff_delay(initial_backoff_ms: u64, max_backoff_ms: u64, attempt: u32) -> Duration {
+    let exponential = initial_backoff_ms
+        .saturating_mul(2u64.saturating_pow(attempt))
+        .min(max_backoff_ms);
+    let jittered = rand::thread_rng().gen_range(0..=exponential);
+    Duration::from_millis(jittered)
+}
+
+// Retry-After is either a number of seconds or an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = date.signed_duration_since(chrono::Utc::now()).num_seconds().max(0);
+    Some(Duration::from_secs(seconds as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn headers(retry_after: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(retry_after).unwrap());
+        headers
+    }
+
+    #[test]
+    fn retry_after_in_seconds() {
+        assert_eq!(retry_after(&headers("120")), Some(Duration::from_secs(120)));
+        assert_eq!(retry_after(&headers(" 5 ")), Some(Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn retry_after_as_an_http_date() {
+        let in_two_minutes = (chrono::Utc::now() + chrono::Duration::seconds(120)).to_rfc2822();
+        let delay = retry_after(&headers(&in_two_minutes)).unwrap();
+        assert!(delay <= Duration::from_secs(120) && delay >= Duration::from_secs(110), "{:?}", delay);
+        // A date already past means right away
+        assert_eq!(retry_after(&headers("Wed, 21 Oct 2015 07:28:00 GMT")), Some(Duration::ZERO));
+    }
+
+    #[test]
+    fn retry_after_missing_or_invalid() {
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+        assert_eq!(retry_after(&headers("soon")), None);
+    }
+
+    #[test]
+    fn backoff_delay_grows_up_to_the_maximum() {
+        for _ in 0..100 {
+            assert!(backoff_delay(500, 30_000, 0) <= Duration::from_millis(500));
+            assert!(backoff_delay(500, 30_000, 3) <= Duration::from_millis(4_000));
+            assert!(backoff_delay(500, 30_000, 10) <= Duration::from_millis(30_000));
+            assert!(backoff_delay(500, 30_000, u32::MAX) <= Duration::from_millis(30_000));
+        }
+        assert_eq!(backoff_delay(0, 30_000, 5), Duration::ZERO);
+    }
+}
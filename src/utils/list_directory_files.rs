@@ -0,0 +1,38 @@
+
+use serde_json::Value;
+use tracing::{debug, info};
+
+use crate::models::AppConfig;
+use crate::utils::fetch_all_pages::fetch_all_pages;
+use crate::types::MyError;
+
+/// List the file paths found below `dir_path` in a repository.
+/// Returned paths are relative to the repository root.
+pub async fn list_directory_files(
+    config: &AppConfig,
+    project_name: &str,
+    repo_name: &str,
+    dir_path: &str,
+) -> Result<Vec<String>, MyError> {
+    let files_url = config.url_config.files_url(project_name, repo_name, dir_path, config.branch.as_deref());
+    info!("Listing files of {} at URL: {}", dir_path, files_url);
+
+    let entries = fetch_all_pages(config, &files_url).await?;
+
+    let files: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            // Bitbucket: { "values": ["ci.yml", "release.yml"], ... }
+            Value::String(name) => Some(format!("{}/{}", dir_path.trim_end_matches('/'), name)),
+            // GitHub: [ { "type": "file", "path": ".github/workflows/ci.yml", ... } ]
+            // Bitbucket Cloud: { "values": [ { "type": "commit_file", "path": "..." } ] }
+            Value::Object(_) if matches!(entry["type"].as_str(), Some("file") | Some("commit_file")) => {
+                entry["path"].as_str().map(str::to_string)
+            }
+            _ => None,
+        })
+        .collect();
+
+    debug!("Files found below {}: {:?}", dir_path, files);
+    Ok(files)
+}
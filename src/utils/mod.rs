@@ -1,8 +1,15 @@
 pub mod append_json_to_file;
 pub mod check_file_exists;
-pub mod create_client_with_auth;
+pub mod create_client;
 pub mod enrich_versions_with_roadmap;
 pub mod remove_null_values;
 pub mod run_json_get_query;
 pub mod run_get_request;
 pub mod download_xml_file;
+pub mod list_directory_files;
+pub mod find_line_number;
+pub mod http_policy;
+pub mod send_with_retry;
+pub mod http_cache;
+pub mod get_head_commit;
+pub mod fetch_all_pages;
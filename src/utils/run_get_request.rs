@@ -1,15 +1,44 @@
 
-use tracing::{error, trace, info};  // Import `trace`
+use reqwest::header::{ACCEPT, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, LINK};
+use chrono::Utc;
+use reqwest::StatusCode;
+use tracing::{debug, error, trace, info};  // Import `trace`
 use crate::models::AppConfig;
-use crate::types::MyError;
+use crate::types::{CustomError, MyError};
+use crate::utils::http_cache::{cache_key, get_cached_response, store_cached_response, CachedResponse};
+use crate::utils::http_policy::HttpStats;
+use crate::utils::send_with_retry::send_with_retry;
 
 pub async fn run_get_request(
     config: &AppConfig,  // Use the config to get headers and client
     url: &str,           // URL to request
 ) -> Result<Option<String>, MyError> {  // Return `Option<String>`
+    Ok(run_get_response(config, url).await?.map(|response| response.body))
+}
+
+// Same as `run_get_request`, keeping the headers needed by the callers (validators, pagination)
+pub async fn run_get_response(
+    config: &AppConfig,
+    url: &str,
+) -> Result<Option<CachedResponse>, MyError> {
     let client = &config.client;
     let headers = config.url_config.get_headers()?;  // Get headers from config
 
+    // `force_git_pull` bypasses the cache, the fresh response still refreshes it
+    let key = cache_key(url, config.head_commit.as_deref());
+    let cached = if config.http.cache_enabled && !config.force_git_pull {
+        get_cached_response(config, &key)
+    } else {
+        None
+    };
+
+    // Content cached for the current head commit cannot have changed
+    if cached.is_some() && config.head_commit.is_some() {
+        debug!("Cache hit for {}", key);
+        HttpStats::increment(&config.http_stats.cache_hits);
+        return Ok(cached);
+    }
+
     // Trace the URL being requested
     trace!("Sending GET request to URL: {}", url);
 
@@ -20,36 +49,74 @@ pub async fn run_get_request(
     for (name, value) in headers {
         request = request.header(name, value);
     }
+    if let Some(accept) = config.url_config.accept_header(url) {
+        request = request.header(ACCEPT, accept);
+    }
+
+    // Conditional request when a previous response is cached
+    if let Some(cached) = &cached {
+        if let Some(etag) = &cached.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &cached.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+    }
+
+    // Send the request with the configured retry policy
+    let response = send_with_retry(config, request, url).await;
 
-    // Send the request
-    let response = request.send();
-    // Ok(Some("".to_string()))
     // Process the response
-    match response.await {
+    match response {
         Ok(resp) => {
             trace!("Received response with status: {}", resp.status());
 
+            if resp.status() == StatusCode::NOT_MODIFIED {
+                if let Some(mut cached) = cached {
+                    debug!("Not modified since last run: {}", url);
+                    HttpStats::increment(&config.http_stats.not_modified);
+                    cached.stored_at = Some(Utc::now());
+                    store_cached_response(config, &key, &cached);
+                    return Ok(Some(cached));
+                }
+            }
+
             if resp.status().is_success() {
+                let header_value = |name| {
+                    resp.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string)
+                };
+                let etag = header_value(ETAG);
+                let last_modified = header_value(LAST_MODIFIED);
+                let link = header_value(LINK);
+
                 // Return the raw response body as text
                 match resp.text().await {
-                    Ok(body) => Ok(Some(body)),  // Wrap in `Some` for successful responses
+                    Ok(body) => {
+                        let response = CachedResponse { etag, last_modified, body, link, stored_at: Some(Utc::now()) };
+                        if config.http.cache_enabled {
+                            store_cached_response(config, &key, &response);
+                        }
+                        Ok(Some(response))  // Wrap in `Some` for successful responses
+                    }
                     Err(e) => {
                         info!("Error reading response body: {}", e);
-                        Err(format!("Error reading response body: {}", e).into())
+                        Err(Box::new(CustomError::Network { url: url.to_string(), source: e }))
                     }
                 }
             } else if resp.status().as_u16() == 404 {
                 info!("Received 404 Not Found for URL: {}", url);
                 Ok(None)  // Return `None` for 404
+            } else if resp.status().as_u16() == 401 || resp.status().as_u16() == 403 {
+                error!("Access denied to {}, status: {}", url, resp.status());
+                Err(CustomError::auth(format!("access denied to {} (HTTP {})", url, resp.status().as_u16())))
             } else {
                 error!("Failed to fetch data from {}, status: {}", url, resp.status());
-                Err(format!("Failed to fetch data, status: {}", resp.status()).into())
+                Err(CustomError::http(url, resp.status().as_u16()))
             }
         }
         Err(e) => {
             error!("Error fetching URL {}: {}", url, e);
-            Err(format!("Error fetching URL {}: {}", url, e).into())
+            Err(e)
         }
     }
 }
-
@@ -6,7 +6,7 @@ use crate::models::AppConfig;
 use crate::utils::run_get_request::run_get_request;
 use serde_json::json;  // For creating empty JSON objects
 
-use crate::types::MyError;
+use crate::types::{CustomError, MyError};
 
 pub async fn run_json_get_query(
     config: &AppConfig,
@@ -28,8 +28,8 @@ pub async fn run_json_get_query(
                     Ok(json)
                 }
                 Err(e) => {
-                    error!("Error parsing repos JSON: {}", e);
-                    Err(format!("Error parsing repos JSON: {}", e).into())
+                    error!("Error parsing JSON from {}: {}", paginated_repos_url, e);
+                    Err(CustomError::parse(format!("JSON from {}", paginated_repos_url), e))
                 }
             }
         }
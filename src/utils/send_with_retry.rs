@@ -0,0 +1,83 @@
+
+use std::time::Duration;
+
+use reqwest::{RequestBuilder, Response, StatusCode};
+use tracing::{debug, warn};
+
+use crate::models::AppConfig;
+use crate::utils::http_policy::{backoff_delay, retry_after, HttpStats};
+use crate::types::{CustomError, MyError};
+
+fn is_retryable(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 429 | 500 | 502 | 503 | 504)
+}
+
+/// Send a request applying the configured retry policy: exponential backoff with jitter,
+/// `Retry-After` on 429/503 (at most `max_backoff_ms`), and a per-host circuit breaker.
+/// The last response is returned as is, callers decide what a non-success status means.
+pub async fn send_with_retry(
+    config: &AppConfig,
+    request: RequestBuilder,
+    url: &str,
+) -> Result<Response, MyError> {
+    let policy = &config.http;
+    let stats = &config.http_stats;
+    let host = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.host_str().map(str::to_string))
+        .unwrap_or_default();
+
+    let mut attempt = 0;
+    loop {
+        if config.circuit_breakers.is_open(&host) {
+            HttpStats::increment(&stats.circuit_rejections);
+            return Err(Box::new(CustomError::CircuitOpen { host }));
+        }
+
+        let attempt_request = request
+            .try_clone()
+            .ok_or_else(|| CustomError::invalid_input(format!("Request to {} cannot be retried", url)))?;
+
+        HttpStats::increment(&stats.requests);
+        let outcome = attempt_request.send().await;
+        let can_retry = attempt < policy.max_retries;
+
+        let delay = match outcome {
+            Ok(resp) if is_retryable(resp.status()) => {
+                let status = resp.status();
+                if status == StatusCode::TOO_MANY_REQUESTS {
+                    HttpStats::increment(&stats.rate_limited);
+                } else {
+                    config.circuit_breakers.record_failure(&host, policy);
+                }
+
+                if !can_retry {
+                    HttpStats::increment(&stats.failures);
+                    return Ok(resp);
+                }
+                retry_after(resp.headers())
+                    .map(|delay| delay.min(Duration::from_millis(policy.max_backoff_ms)))
+                    .unwrap_or_else(|| backoff_delay(policy.initial_backoff_ms, policy.max_backoff_ms, attempt))
+            }
+            Ok(resp) => {
+                config.circuit_breakers.record_success(&host);
+                return Ok(resp);
+            }
+            Err(e) if (e.is_timeout() || e.is_connect()) && can_retry => {
+                config.circuit_breakers.record_failure(&host, policy);
+                debug!("Transient error for {}: {}", url, e);
+                backoff_delay(policy.initial_backoff_ms, policy.max_backoff_ms, attempt)
+            }
+            Err(e) => {
+                config.circuit_breakers.record_failure(&host, policy);
+                HttpStats::increment(&stats.failures);
+                return Err(Box::new(CustomError::Network { url: url.to_string(), source: e }));
+            }
+        };
+
+        attempt += 1;
+        HttpStats::increment(&stats.retries);
+        warn!("Retrying {} in {:?} (attempt {}/{})", url, delay, attempt, policy.max_retries);
+        tokio::time::sleep(delay).await;
+    }
+}
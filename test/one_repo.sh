@@ -2,7 +2,8 @@
 
 # Replace these with appropriate values
 REPO_NAME="gpecs"  # Replace with the actual repository name you want to analyze
-TENANT="mayorana"         # Replace with your tenant if needed
+TENANT="${XDEBT_TENANT:-}" # One of the configured `tenants`, the top level configuration when empty
+TOKEN="${XDEBT_TOKEN:-}"  # Bearer token when `auth.tokens` is configured
 
 # Prepare the JSON payload for the request
 REQUEST_PAYLOAD=$(cat <<EOF
@@ -14,7 +15,8 @@ EOF
 
 # Use grpcurl to send the request to your gRPC service
 grpcurl -plaintext \
-    -rpc-header "tenant:$TENANT" \
+    ${TENANT:+-rpc-header "tenant:$TENANT"} \
+    ${TOKEN:+-rpc-header "authorization: Bearer $TOKEN"} \
     -d "$REQUEST_PAYLOAD" \
-    0.0.0.0:50051 \
+    0.0.0.0:50052 \
     analyze.Analyze/AnalyzeRepository